@@ -0,0 +1,52 @@
+#ifndef WUF_H
+#define WUF_H
+
+/* C bindings of the wuf crate, built with the `ffi` feature. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque union-find over a fixed range of nodes. */
+typedef struct WufGraph WufGraph;
+
+/* Returns a new graph with the given number of nodes, to be released with
+ * wuf_graph_free, or null if there are too many nodes for the graph, or if
+ * their array of node IDs cannot be allocated. */
+WufGraph *wuf_graph_new(size_t n);
+
+/* Releases the given graph, unless null. */
+void wuf_graph_free(WufGraph *graph);
+
+/* Returns the number of nodes of the given graph. */
+size_t wuf_graph_count(const WufGraph *graph);
+
+/* Returns the number of disjoint components of the given graph. */
+size_t wuf_graph_component_count(const WufGraph *graph);
+
+/* Connects the two given nodes, and returns 1 if they belonged to different
+ * components, 0 if they were already connected, or -1 if a node is out of
+ * bounds. */
+int32_t wuf_graph_union(WufGraph *graph, size_t a, size_t b);
+
+/* Connects the len edges stored as consecutive pairs of node IDs in edges,
+ * and returns the number of merges, or -1 if a node is out of bounds, after
+ * connecting the edges before it. */
+ptrdiff_t wuf_graph_union_all(WufGraph *graph, const size_t *edges, size_t len);
+
+/* Returns 1 if the two given nodes are connected, 0 if they are not, or -1 if
+ * a node is out of bounds. */
+int32_t wuf_graph_connected(WufGraph *graph, size_t a, size_t b);
+
+/* Stores the root of the given node in root (unless null), and returns 0, or
+ * returns -1 if the node is out of bounds. */
+int32_t wuf_graph_find(WufGraph *graph, size_t id, size_t *root);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WUF_H */
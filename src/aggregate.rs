@@ -0,0 +1,144 @@
+use std::fmt;
+
+use graph::Graph;
+
+/// Weighted union-find with path compression where every node has a value,
+/// and every component maintains the aggregate of the values of its nodes,
+/// combined on every union by the given function (such as a sum, a minimum or
+/// a maximum).
+/// 
+/// The combine function must be associative and commutative, since values
+/// are combined in no particular order.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::AggregateGraph::new(vec![5, 3, 8, 1], |a: &u32, b: &u32| a + b);
+/// graph.connect(0, 1);
+/// graph.connect(2, 1);
+/// assert_eq!(&16, graph.aggregate(0));
+/// assert_eq!(&1, graph.aggregate(3));
+/// ```
+#[derive(Clone)]
+pub struct AggregateGraph<T, F> {
+    graph: Graph,       // components of the nodes
+    aggregates: Vec<T>, // aggregate of the component of each root
+    combine: F          // combines the aggregates of two components
+}
+
+impl<T, F: Fn(&T, &T) -> T> AggregateGraph<T, F> {
+
+    /// Returns a new AggregateGraph with a node for each of the given values.
+    /// 
+    /// # Arguments
+    /// * `values` The value of each node.
+    /// * `combine` Combines the aggregates of two components.
+    pub fn new(values: Vec<T>, combine: F) -> AggregateGraph<T, F> {
+        AggregateGraph { graph: Graph::new(values.len()), aggregates: values, combine }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.graph.count()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.graph.component_count()
+    }
+
+    /// Returns the aggregate of the values of the nodes in the component the
+    /// given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn aggregate(&mut self, id: usize) -> &T {
+        let root = self.graph.find(id);
+        &self.aggregates[root]
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> usize {
+        self.graph.find(id)
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.graph.connected(a, b)
+    }
+
+    /// Connects the two given nodes, combining the aggregates of their
+    /// components.
+    /// 
+    /// Returns true only if the two nodes belonged to different components.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::cmp;
+    /// 
+    /// let mut graph = wuf::AggregateGraph::new(vec![5, 3, 8], |a: &u32, b: &u32| cmp::min(*a, *b));
+    /// assert!(graph.connect(0, 1));
+    /// assert!(!graph.connect(1, 0));
+    /// assert_eq!(&3, graph.aggregate(0));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> bool {
+        let a_root = self.graph.find(a);
+        let b_root = self.graph.find(b);
+        match self.graph.connect(a_root, b_root) {
+            Some(root) => {
+                let aggregate = (self.combine)(&self.aggregates[a_root], &self.aggregates[b_root]);
+                self.aggregates[root] = aggregate;
+                true
+            }
+            None => false
+        }
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for AggregateGraph<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the aggregates of non-root nodes are stale, and not shown
+        let aggregates: Vec<(usize, &T)> = self.graph.parents().enumerate()
+            .filter(|&(id, parent)| id == parent)
+            .map(|(id, _)| (id, &self.aggregates[id]))
+            .collect();
+        f.debug_struct("AggregateGraph")
+            .field("graph", &self.graph)
+            .field("aggregates", &aggregates)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_aggregate_components() {
+        let values: Vec<(u32, u32)> = (0..8).map(|id| (id, id)).collect();
+        // minimum and maximum of every component
+        let mut graph = AggregateGraph::new(values, |a: &(u32, u32), b: &(u32, u32)| {
+            (a.0.min(b.0), a.1.max(b.1))
+        });
+        for &(a, b) in &[(3, 5), (5, 1), (6, 7), (1, 3), (0, 6)] {
+            graph.connect(a, b);
+        }
+        assert_eq!(4, graph.component_count());
+        assert_eq!(&(1, 5), graph.aggregate(3));
+        assert_eq!(&(0, 7), graph.aggregate(7));
+        assert_eq!(&(2, 2), graph.aggregate(2));
+        let debug = format!("{:?}", graph);
+        assert!(debug.contains("(2, (2, 2))"));
+    }
+}
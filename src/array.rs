@@ -0,0 +1,189 @@
+use std::array;
+
+use union_find::UnionFind;
+
+/// Weighted union-find over a fixed number of nodes `N`, stored inline in
+/// arrays rather than on the heap, so that it never allocates and can live
+/// on the stack (best suited to small `N`).
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::ArrayGraph::<8>::new();
+/// graph.connect(0, 1);
+/// assert!(graph.connected(1, 0));
+/// assert_eq!(7, graph.component_count());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayGraph<const N: usize> {
+    parents: [usize; N],    // parent of each node
+    sizes: [usize; N],      // size of the tree rooted at each node
+    components: usize       // number of disjoint components
+}
+
+impl<const N: usize> ArrayGraph<N> {
+
+    /// Returns a new ArrayGraph of `N` nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::ArrayGraph::<10>::new();
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new() -> ArrayGraph<N> {
+        ArrayGraph { parents: array::from_fn(|id| id), sizes: [1; N], components: N }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::ArrayGraph::<10>::new();
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::ArrayGraph::<10>::new();
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::ArrayGraph::<10>::new();
+    /// graph.connect(0, 1);
+    /// assert_eq!(2, graph.component_size(1));
+    /// ```
+    pub fn component_size(&mut self, id: usize) -> usize {
+        let root = self.find(id);
+        self.sizes[root]
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::ArrayGraph::<10>::new();
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::ArrayGraph::<10>::new();
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        // balance by linking root of smaller tree to root of larger tree
+        let (root, child) = if self.sizes[a_root] < self.sizes[b_root] {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::ArrayGraph::<10>::new();
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        let mut node = id;
+        while node != self.parents[node] {
+            // make every other node in path point to its grandparent
+            let grandparent = self.parents[self.parents[node]];
+            self.parents[node] = grandparent;
+            node = grandparent;
+        }
+        node
+    }
+}
+
+impl<const N: usize> Default for ArrayGraph<N> {
+    fn default() -> ArrayGraph<N> {
+        ArrayGraph::new()
+    }
+}
+
+impl<const N: usize> UnionFind for ArrayGraph<N> {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        ArrayGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        ArrayGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect() {
+        let mut graph = ArrayGraph::<6>::default();
+        for &(a, b) in &[(0, 1), (2, 3), (1, 3), (4, 5)] {
+            graph.connect(a, b);
+        }
+        let snapshot = graph;
+        assert_eq!(2, graph.component_count());
+        assert_eq!(4, graph.component_size(2));
+        assert!(graph.connected(0, 2));
+        assert!(!graph.connected(0, 5));
+        assert_eq!(2, snapshot.component_count());
+        assert_eq!(0, ArrayGraph::<0>::new().count());
+    }
+}
@@ -0,0 +1,253 @@
+//! Computes the connected components of the graph described by an edge list.
+//! 
+//! Reads the edges (a pair of node IDs per line, see
+//! `Graph::from_edge_reader`) from the given file, or from the standard input
+//! if no file is given, and prints the label of every node, where components
+//! are labeled in order of their smallest node ID. With `--members`, prints
+//! the nodes of every component instead, one component per line.
+//! 
+//! Unless given with `-n`, the number of nodes is one more than the largest
+//! node ID found in the edges. Either way, the edges are read one line at a
+//! time, and the number of nodes is bounded by `--max-nodes` (2^24 by
+//! default), so that a malformed input is reported as an error instead of
+//! allocating the memory for an arbitrary number of nodes.
+//! 
+//! With `--ops`, reads commands from the standard input instead, one per line,
+//! and prints the answer to each command as soon as it is read:
+//! * `union A B` connects the two nodes, and prints `true` only if they
+//!   belonged to different components.
+//! * `query A B` prints `true` only if the two nodes are connected.
+//! * `count` prints the number of components.
+//! 
+//! The graph starts with the number of nodes given with `-n` (if any), and
+//! grows to include every node connected by `union`, up to the number of
+//! nodes given with `--max-nodes`, while `query` never grows the graph. Malformed commands, and nodes beyond the maximum, are
+//! answered with a line starting with `error:`.
+
+extern crate wuf;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+const USAGE: &str = "usage: wuf [--members] [-n COUNT] [--max-nodes COUNT] [FILE]\n       wuf --ops [-n COUNT] [--max-nodes COUNT]";
+// default maximum number of nodes of the graph
+const DEFAULT_MAX_NODES: usize = 1 << 24;
+
+/// Command line options.
+struct Options {
+    members: bool,          // print the members of each component
+    ops: bool,              // read commands from the standard input
+    count: Option<usize>,   // number of nodes, if given
+    max_nodes: usize,       // maximum number of nodes of the graph
+    path: Option<String>    // path of the edge list, if not the standard input
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("wuf: {}\n{}", message, USAGE);
+        process::exit(2);
+    });
+    let result = if options.ops {
+        run_ops(&options)
+    } else {
+        run(&options)
+    };
+    if let Err(err) = result {
+        eprintln!("wuf: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Parses the command line arguments.
+fn parse_args<A: Iterator<Item = String>>(mut args: A) -> Result<Options, String> {
+    let mut options = Options {
+        members: false,
+        ops: false,
+        count: None,
+        max_nodes: DEFAULT_MAX_NODES,
+        path: None
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--members" => options.members = true,
+            "--ops" => options.ops = true,
+            "-n" => {
+                let count = args.next().ok_or("missing value for -n")?;
+                let count = count.parse()
+                    .map_err(|_| format!("invalid node count `{}`", count))?;
+                options.count = Some(count);
+            }
+            "--max-nodes" => {
+                let max = args.next().ok_or("missing value for --max-nodes")?;
+                options.max_nodes = max.parse()
+                    .map_err(|_| format!("invalid node count `{}`", max))?;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ if options.path.is_some() => {
+                return Err(format!("unexpected argument `{}`", arg));
+            }
+            _ => options.path = Some(arg)
+        }
+    }
+    if options.ops && (options.members || options.path.is_some()) {
+        return Err("--ops reads commands from the standard input only".to_string());
+    }
+    Ok(options)
+}
+
+/// Reads the edge list and prints the components.
+fn run(options: &Options) -> io::Result<()> {
+    let stdin = io::stdin();
+    let reader: Box<dyn BufRead> = match options.path.as_ref().filter(|path| path.as_str() != "-") {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(stdin.lock())
+    };
+    let max_nodes = options.max_nodes.min(wuf::Graph::<usize>::MAX_NODES);
+    let graph = match options.count {
+        Some(n) if n > max_nodes => {
+            let message = format!("{} nodes exceed the limit of {}", n, max_nodes);
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        }
+        Some(n) => wuf::Graph::from_edge_reader(n, reader)?,
+        None => read_growing(reader, max_nodes)?
+    };
+
+    // components are labeled in order of their smallest node ID
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    if options.members {
+        for component in graph.into_sets() {
+            let line: Vec<String> = component.iter().map(|id| id.to_string()).collect();
+            writeln!(out, "{}", line.join(" "))?;
+        }
+    } else {
+        for (id, label) in graph.labels().into_iter().enumerate() {
+            writeln!(out, "{} {}", id, label)?;
+        }
+    }
+    out.flush()
+}
+
+/// Reads the edge list in the format of `Graph::from_edge_reader`, growing the
+/// graph to include every node found in the edges, up to the given number of
+/// nodes.
+fn read_growing<R: BufRead>(reader: R, max_nodes: usize) -> io::Result<wuf::Graph> {
+    let mut graph = wuf::Graph::new(0);
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let edge = parse_edge(&line).and_then(|edge| match edge {
+            Some((a, b)) => {
+                let max = a.max(b);
+                max.checked_add(1)
+                    .filter(|&count| count <= max_nodes)
+                    .map(|count| Some((a, b, count)))
+                    .ok_or_else(|| format!(
+                        "node {} exceeds the limit of {} nodes",
+                        max, max_nodes
+                    ))
+            }
+            None => Ok(None)
+        });
+        match edge {
+            Ok(Some((a, b, count))) => {
+                if count > graph.count() {
+                    let added = count - graph.count();
+                    graph.add_nodes(added);
+                }
+                graph.connect(a, b);
+            }
+            Ok(None) => {}
+            Err(message) => {
+                let message = format!("line {}: {}", i + 1, message);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Parses an edge as a pair of node IDs separated by whitespace or by a comma,
+/// returning `None` if the line is empty or is a comment starting with `#`.
+fn parse_edge(line: &str) -> Result<Option<(usize, usize)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let fields: Vec<&str> = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .collect();
+    if fields.len() != 2 {
+        return Err("expected two node IDs".to_string());
+    }
+    let parse = |field: &str| -> Result<usize, String> {
+        field.parse().map_err(|_| format!("invalid node ID `{}`", field))
+    };
+    Ok(Some((parse(fields[0])?, parse(fields[1])?)))
+}
+
+/// Answers the commands read from the standard input.
+fn run_ops(options: &Options) -> io::Result<()> {
+    let mut graph = wuf::Graph::new(options.count.unwrap_or(0));
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match answer(&mut graph, line, options.max_nodes) {
+            Ok(answer) => writeln!(out, "{}", answer)?,
+            Err(message) => writeln!(out, "error: {}", message)?
+        }
+        // the caller may wait for the answer before sending the next command
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Executes the given command and returns its answer, growing the graph for
+/// `union` up to the given number of nodes.
+fn answer(graph: &mut wuf::Graph, command: &str, max_nodes: usize) -> Result<String, String> {
+    let mut fields = command.split_whitespace();
+    let name = fields.next().unwrap_or_default();
+    let mut ids = Vec::with_capacity(2);
+    for field in fields {
+        let id: usize = field.parse()
+            .map_err(|_| format!("invalid node ID `{}`", field))?;
+        ids.push(id);
+    }
+    let arity = if name == "count" { 0 } else { 2 };
+    if !matches!(name, "union" | "query" | "count") {
+        return Err(format!("unknown command `{}`", name));
+    }
+    if ids.len() != arity {
+        return Err(format!("`{}` expects {} node IDs", name, arity));
+    }
+    let max = ids.iter().max().cloned().unwrap_or_default();
+    Ok(match name {
+        // nodes not added yet are only connected to themselves
+        "query" if max >= graph.count() => (ids[0] == ids[1]).to_string(),
+        "query" => graph.connected(ids[0], ids[1]).to_string(),
+        "union" => {
+            if max >= graph.count() {
+                if max >= max_nodes.min(wuf::Graph::<usize>::MAX_NODES) {
+                    return Err(format!("node {} out of bounds", max));
+                }
+                graph.add_nodes(max + 1 - graph.count());
+            }
+            graph.connect(ids[0], ids[1]).is_some().to_string()
+        }
+        _ => graph.component_count().to_string()
+    })
+}
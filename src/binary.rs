@@ -0,0 +1,204 @@
+use std::io::{self, Read, Write};
+
+use graph::{Compression, Graph, Linking};
+use index::Index;
+
+// identifies the binary format
+const MAGIC: &[u8; 4] = b"WUF\0";
+// current version of the binary format
+const VERSION: u8 = 1;
+// number of entries encoded or decoded at once
+const CHUNK: usize = 8192;
+
+impl<I: Index> Graph<I> {
+
+    /// Writes the graph to the given writer, using a compact binary format.
+    /// 
+    /// The format starts with a 16 bytes header (magic number, version, width
+    /// of the parents, linking and compression strategies, and number of
+    /// nodes), followed by the parent of each node (roots are their own
+    /// parent) as little-endian integers of 4 bytes, or 8 bytes if the number
+    /// of nodes does not fit in 4 bytes, followed by the rank of each node if
+    /// linking by rank.
+    /// 
+    /// Returns an error of kind `InvalidInput` if any node is retired (see
+    /// `remove_node`), since the format does not record which ones.
+    /// 
+    /// # Arguments
+    /// * `writer` The writer to write the graph to.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// let mut bytes = Vec::new();
+    /// graph.write_to(&mut bytes).unwrap();
+    /// let mut other: wuf::Graph = wuf::Graph::read_from(&mut bytes.as_slice()).unwrap();
+    /// assert!(other.connected(0, 1));
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.check_not_retired().map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let n = self.count();
+        let width = if n as u64 <= u64::from(u32::MAX) { 4 } else { 8 };
+        let mut header = [0; 16];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = VERSION;
+        header[5] = width as u8;
+        header[6] = match self.linking() {
+            Linking::Size => 0,
+            Linking::Rank => 1
+        };
+        header[7] = match self.compression() {
+            Compression::Full => 0,
+            Compression::Halving => 1,
+            Compression::Splitting => 2,
+            Compression::None => 3
+        };
+        header[8..].copy_from_slice(&(n as u64).to_le_bytes());
+        writer.write_all(&header)?;
+        let mut buffer = Vec::with_capacity(CHUNK * width);
+        for (id, parent) in self.parents().enumerate() {
+            let bytes = (parent as u64).to_le_bytes();
+            buffer.extend_from_slice(&bytes[..width]);
+            if (id + 1) % CHUNK == 0 {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+        writer.write_all(&buffer)?;
+        writer.write_all(self.ranks())
+    }
+
+    /// Reads a graph from the given reader, previously written with
+    /// `write_to`, checking that the parents describe a valid forest.
+    /// 
+    /// Returns an error of kind `InvalidData` if the data is malformed.
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the graph from.
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Graph<I>> {
+        let mut header = [0; 16];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data("not a wuf graph"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_data("unsupported version"));
+        }
+        let width = header[5] as usize;
+        if width != 4 && width != 8 {
+            return Err(invalid_data("unsupported width"));
+        }
+        let linking = match header[6] {
+            0 => Linking::Size,
+            1 => Linking::Rank,
+            _ => return Err(invalid_data("unknown linking strategy"))
+        };
+        let compression = match header[7] {
+            0 => Compression::Full,
+            1 => Compression::Halving,
+            2 => Compression::Splitting,
+            3 => Compression::None,
+            _ => return Err(invalid_data("unknown compression strategy"))
+        };
+        let mut count = [0; 8];
+        count.copy_from_slice(&header[8..]);
+        let n = u64::from_le_bytes(count);
+        if n > Self::MAX_NODES as u64 {
+            return Err(invalid_data("too many nodes"));
+        }
+        let n = n as usize;
+        // grow one chunk at a time, so that a header claiming more nodes than
+        // the data holds does not allocate for them
+        let mut parents = Vec::new();
+        let mut buffer = vec![0; CHUNK * width];
+        while parents.len() < n {
+            let len = (n - parents.len()).min(CHUNK) * width;
+            reader.read_exact(&mut buffer[..len])?;
+            parents.reserve(len / width);
+            parents.extend(buffer[..len].chunks(width).map(|bytes| {
+                let mut parent = [0; 8];
+                parent[..width].copy_from_slice(bytes);
+                u64::from_le_bytes(parent) as usize
+            }));
+        }
+        let mut ranks = Vec::new();
+        if linking == Linking::Rank {
+            ranks.resize(n, 0);
+            reader.read_exact(&mut ranks)?;
+        }
+        Graph::from_checked_parts(parents, None, ranks, linking, compression)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// Returns a new error of kind `InvalidData` with the given message.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_roundtrip() {
+        let mut graph = Graph::with_linking(CHUNK + 10, Linking::Rank);
+        for id in (0..CHUNK + 10).step_by(3) {
+            graph.connect(id, (id * 7) % (CHUNK + 10));
+        }
+        let mut bytes = Vec::new();
+        graph.write_to(&mut bytes).unwrap();
+        assert_eq!(16 + (CHUNK + 10) * 5, bytes.len());
+        let other = Graph::<u32>::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(graph.component_count(), other.component_count());
+        assert_eq!(graph.parents_and_sizes(), other.parents_and_sizes());
+        assert_eq!(graph.ranks(), other.ranks());
+        assert_eq!(Linking::Rank, other.linking());
+    }
+
+    #[test]
+    fn should_reject_malformed_data() {
+        let mut bytes = Vec::new();
+        Graph::new(3).write_to(&mut bytes).unwrap();
+        // point the first node to a node out of bounds
+        bytes[16] = 3;
+        let err = Graph::<usize>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        let err = Graph::<usize>::read_from(&mut &bytes[1..]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        let err = Graph::<usize>::read_from(&mut &bytes[..20]).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+        // retired nodes cannot be written
+        let mut graph = Graph::new(3);
+        graph.remove_node(1);
+        let err = graph.write_to(&mut Vec::new()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    fn should_reject_invalid_ranks() {
+        let mut bytes = Vec::new();
+        Graph::with_linking(2, Linking::Rank).write_to(&mut bytes).unwrap();
+        assert_eq!(16 + 2 * 4 + 2, bytes.len());
+        // ranks that would overflow when linking the two roots
+        bytes[24] = 255;
+        bytes[25] = 255;
+        let err = Graph::<usize>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("rank 255 of node 0 is not consistent with its tree", err.to_string());
+    }
+
+    #[test]
+    fn should_not_allocate_for_missing_nodes() {
+        let mut bytes = Vec::new();
+        Graph::new(3).write_to(&mut bytes).unwrap();
+        // claim far more nodes than the data holds
+        bytes[5] = 8;
+        bytes[8..16].copy_from_slice(&(1u64 << 60).to_le_bytes());
+        let err = Graph::<usize>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+        let err = Graph::<usize>::read_from(&mut &bytes[..16]).unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    }
+}
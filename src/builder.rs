@@ -0,0 +1,145 @@
+use std::marker::PhantomData;
+use std::vec::Vec;
+
+use graph::{Compression, Graph, Linking};
+use index::Index;
+
+/// Builder of a `Graph`, that bundles its configuration: index type, number
+/// of nodes, capacity, linking and compression strategies, whether to track
+/// the members of each component, and the edges to connect once built.
+/// 
+/// # Example
+/// ```
+/// use wuf::{Compression, GraphBuilder, Linking};
+/// 
+/// let graph = GraphBuilder::new(10)
+///     .index::<u16>()
+///     .capacity(100)
+///     .linking(Linking::Rank)
+///     .compression(Compression::Full)
+///     .edges(vec![(0, 1), (1, 2)])
+///     .build();
+/// assert_eq!(10, graph.count());
+/// assert!(graph.capacity() >= 100);
+/// assert_eq!(Linking::Rank, graph.linking());
+/// assert_eq!(8, graph.component_count());
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<I: Index = usize> {
+    nodes: usize,               // number of nodes of the graph
+    capacity: usize,            // number of nodes the graph can hold
+    linking: Linking,           // strategy used to link roots
+    compression: Compression,   // strategy used to compress paths
+    members: bool,              // whether to track the members of each component
+    edges: Vec<(usize, usize)>, // edges to connect once built
+    index: PhantomData<I>       // index type storing node IDs
+}
+
+impl GraphBuilder {
+
+    /// Returns a new builder of a graph with the given number of nodes, and
+    /// with the default configuration of `Graph::new`.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::GraphBuilder::new(10).build();
+    /// assert_eq!(10, graph.component_count());
+    /// ```
+    pub fn new(n: usize) -> GraphBuilder {
+        GraphBuilder {
+            nodes: n,
+            capacity: n,
+            linking: Linking::default(),
+            compression: Compression::default(),
+            members: false,
+            edges: Vec::new(),
+            index: PhantomData
+        }
+    }
+}
+
+impl<I: Index> GraphBuilder<I> {
+
+    /// Sets the index type used to store node IDs (see `Index`).
+    pub fn index<J: Index>(self) -> GraphBuilder<J> {
+        GraphBuilder {
+            nodes: self.nodes,
+            capacity: self.capacity,
+            linking: self.linking,
+            compression: self.compression,
+            members: self.members,
+            edges: self.edges,
+            index: PhantomData
+        }
+    }
+
+    /// Sets the number of nodes the graph can hold without reallocating,
+    /// which is never less than the number of nodes.
+    pub fn capacity(mut self, capacity: usize) -> GraphBuilder<I> {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the strategy used to link components.
+    pub fn linking(mut self, linking: Linking) -> GraphBuilder<I> {
+        self.linking = linking;
+        self
+    }
+
+    /// Sets the strategy used to compress paths.
+    pub fn compression(mut self, compression: Compression) -> GraphBuilder<I> {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets whether to track the members of each component in circular lists,
+    /// so that `Graph::members` takes time linear in the size of the component
+    /// rather than in the number of nodes, at the cost of one more index per
+    /// node (false by default).
+    pub fn track_members(mut self, track: bool) -> GraphBuilder<I> {
+        self.members = track;
+        self
+    }
+
+    /// Adds the given edges to the ones to connect once built.
+    pub fn edges<E: IntoIterator<Item = (usize, usize)>>(mut self, edges: E) -> GraphBuilder<I> {
+        self.edges.extend(edges);
+        self
+    }
+
+    /// Returns the graph, after connecting the edges in order.
+    /// 
+    /// Panics if the number of nodes exceeds `Graph::<I>::MAX_NODES`, or if an
+    /// edge refers to a node that does not belong to the graph.
+    pub fn build(self) -> Graph<I> {
+        let mut graph = Graph::with_config(self.nodes, self.capacity, self.linking, self.compression);
+        if self.members {
+            graph.track_members();
+        }
+        graph.connect_all(self.edges);
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build() {
+        let graph = GraphBuilder::new(6)
+            .compression(Compression::None)
+            .edges(vec![(0, 1)])
+            .edges(vec![(1, 2), (4, 5)])
+            .index::<u8>()
+            .build();
+        assert_eq!(Compression::None, graph.compression());
+        assert_eq!(Linking::Size, graph.linking());
+        assert!(graph.same_partition(&Graph::from_edges(6, vec![(0, 2), (2, 1), (5, 4)])));
+        assert_eq!(6, GraphBuilder::new(6).capacity(2).build().capacity());
+        assert!(GraphBuilder::new(6).capacity(9).track_members(true).build().capacity() >= 9);
+    }
+}
@@ -0,0 +1,210 @@
+//! Single-linkage clustering of weighted pairs.
+//! 
+//! Pairs are given as `(u, v, w)` triples of two node IDs and their distance,
+//! and are merged in ascending order of distance, as by Kruskal's algorithm.
+
+use std::cmp::Ordering;
+
+use graph::Graph;
+use mst::sorted_edges;
+
+/// Condition that stops the merging of clusters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop<W> {
+    /// Merge only the pairs whose distance is at most the given threshold.
+    Threshold(W),
+    /// Merge until the given number of clusters remain (or the pairs are
+    /// exhausted).
+    Clusters(usize)
+}
+
+/// Returns the cluster label of every node, computed by single-linkage
+/// clustering of the given pairs, where clusters are labeled from 0 in order
+/// of their smallest node ID.
+/// 
+/// Pairs of equal distance are merged in order of index, while incomparable
+/// distances (such as `NaN`) are treated as equal.
+/// 
+/// Panics if a pair refers to a node out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of nodes to cluster.
+/// * `pairs` Distances between pairs of nodes.
+/// * `stop` Condition that stops the merging of clusters.
+/// 
+/// # Example
+/// ```
+/// use wuf::cluster::{single_linkage, Stop};
+/// 
+/// let pairs = [(0, 1, 1.0), (2, 3, 1.5), (1, 2, 4.0), (3, 4, 9.0)];
+/// assert_eq!(vec![0, 0, 1, 1, 2], single_linkage(5, &pairs, Stop::Threshold(2.0)));
+/// assert_eq!(vec![0, 0, 0, 0, 1], single_linkage(5, &pairs, Stop::Clusters(2)));
+/// ```
+pub fn single_linkage<W: PartialOrd>(
+    n: usize,
+    pairs: &[(usize, usize, W)],
+    stop: Stop<W>
+) -> Vec<usize> {
+    let mut graph = Graph::new(n);
+    for id in sorted_edges(pairs, false) {
+        let (a, b, ref distance) = pairs[id];
+        match stop {
+            Stop::Threshold(ref threshold) => match distance.partial_cmp(threshold) {
+                Some(Ordering::Greater) => break,
+                // a distance incomparable with the threshold is never merged
+                None => continue,
+                _ => {}
+            },
+            Stop::Clusters(k) if graph.component_count() <= k => break,
+            Stop::Clusters(_) => {}
+        }
+        graph.connect(a, b);
+    }
+    graph.labels()
+}
+
+/// Merge of two clusters of a dendrogram.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge<W> {
+    /// ID of the first merged cluster.
+    pub a: usize,
+    /// ID of the second merged cluster.
+    pub b: usize,
+    /// Distance between the two merged clusters.
+    pub weight: W,
+    /// Number of nodes in the resulting cluster.
+    pub size: usize
+}
+
+/// Sequence of merges performed by single-linkage clustering, in ascending
+/// order of distance, that can be cut at any level without clustering again.
+/// 
+/// Clusters are identified as in SciPy: the IDs from 0 to `n - 1` are the
+/// single nodes, while the ID `n + i` is the cluster created by the `i`-th
+/// merge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dendrogram<W> {
+    n: usize,               // number of nodes
+    merges: Vec<Merge<W>>   // merges in ascending order of distance
+}
+
+impl<W: PartialOrd + Clone> Dendrogram<W> {
+
+    /// Returns the dendrogram of the single-linkage clustering of the given
+    /// pairs, merged until no pair is left.
+    /// 
+    /// Panics if a pair refers to a node out of bounds.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes to cluster.
+    /// * `pairs` Distances between pairs of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let pairs = [(0, 1, 1.0), (1, 2, 4.0)];
+    /// let dendrogram = wuf::cluster::Dendrogram::new(3, &pairs);
+    /// let merge = &dendrogram.merges()[1];
+    /// assert_eq!((3, 2, 4.0, 3), (merge.a, merge.b, merge.weight, merge.size));
+    /// ```
+    pub fn new(n: usize, pairs: &[(usize, usize, W)]) -> Dendrogram<W> {
+        let mut graph = Graph::new(n);
+        // cluster ID of the component of every root
+        let mut clusters: Vec<usize> = (0..n).collect();
+        let mut merges = Vec::with_capacity(n.saturating_sub(1));
+        for id in sorted_edges(pairs, false) {
+            let (a, b, ref weight) = pairs[id];
+            let (a, b) = (graph.find(a), graph.find(b));
+            if let Some(root) = graph.connect(a, b) {
+                merges.push(Merge {
+                    a: clusters[a],
+                    b: clusters[b],
+                    weight: weight.clone(),
+                    size: graph.component_size(root)
+                });
+                clusters[root] = n + merges.len() - 1;
+            }
+        }
+        Dendrogram { n, merges }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the merges, in ascending order of distance.
+    pub fn merges(&self) -> &[Merge<W>] {
+        &self.merges
+    }
+
+    /// Returns the cluster label of every node, after performing the merges
+    /// allowed by the given condition, where clusters are labeled from 0 in
+    /// order of their smallest node ID (see `single_linkage`).
+    /// 
+    /// # Arguments
+    /// * `stop` Condition that stops the merging of clusters.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::cluster::{Dendrogram, Stop};
+    /// 
+    /// let pairs = [(0, 1, 1.0), (2, 3, 1.5), (1, 2, 4.0), (3, 4, 9.0)];
+    /// let dendrogram = Dendrogram::new(5, &pairs);
+    /// assert_eq!(vec![0, 0, 1, 1, 2], dendrogram.cut(Stop::Threshold(2.0)));
+    /// assert_eq!(vec![0, 0, 0, 0, 1], dendrogram.cut(Stop::Clusters(2)));
+    /// ```
+    pub fn cut(&self, stop: Stop<W>) -> Vec<usize> {
+        // a node for every cluster, connected to the clusters merged into it
+        let mut graph = Graph::new(self.n + self.merges.len());
+        for (i, merge) in self.merges.iter().enumerate() {
+            match stop {
+                Stop::Threshold(ref threshold) => match merge.weight.partial_cmp(threshold) {
+                    Some(Ordering::Greater) => break,
+                    None => continue,
+                    _ => {}
+                },
+                Stop::Clusters(k) if self.n - i <= k => break,
+                Stop::Clusters(_) => {}
+            }
+            graph.connect(merge.a, self.n + i);
+            graph.connect(merge.b, self.n + i);
+        }
+        // every component includes at least one node, labeled first
+        let mut labels = graph.labels();
+        labels.truncate(self.n);
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_cluster() {
+        let pairs = [(4, 3, 2), (0, 1, 1), (1, 2, 2), (0, 2, 1), (2, 3, 3)];
+        assert_eq!(vec![0, 1, 2, 3, 4], single_linkage(5, &pairs, Stop::Threshold(0)));
+        assert_eq!(vec![0, 0, 0, 1, 1], single_linkage(5, &pairs, Stop::Threshold(2)));
+        assert_eq!(vec![0, 0, 0, 1, 2], single_linkage(5, &pairs, Stop::Clusters(3)));
+        assert_eq!(vec![0; 5], single_linkage(5, &pairs, Stop::Clusters(0)));
+        assert_eq!(vec![0, 1], single_linkage::<u32>(2, &[], Stop::Clusters(1)));
+    }
+
+    #[test]
+    fn should_cut_dendrogram() {
+        let pairs = [(4, 3, 2), (0, 1, 1), (1, 2, 2), (0, 2, 1), (2, 3, 3)];
+        let dendrogram = Dendrogram::new(5, &pairs);
+        let merges: Vec<_> = dendrogram.merges().iter()
+            .map(|merge| (merge.a, merge.b, merge.weight, merge.size))
+            .collect();
+        assert_eq!(vec![(0, 1, 1, 2), (5, 2, 1, 3), (4, 3, 2, 2), (6, 7, 3, 5)], merges);
+        for &threshold in &[0, 1, 2, 3] {
+            let stop = Stop::Threshold(threshold);
+            assert_eq!(single_linkage(5, &pairs, stop), dendrogram.cut(stop));
+        }
+        for k in 0..6 {
+            let stop = Stop::Clusters(k);
+            assert_eq!(single_linkage(5, &pairs, stop), dendrogram.cut(stop));
+        }
+    }
+}
@@ -0,0 +1,786 @@
+//! Union-find structures that can be shared across threads, so that multiple
+//! threads can connect and query nodes at the same time.
+//! 
+//! With the `parallel` feature, `Graph` also gains methods that split their
+//! work among threads, such as `Graph::par_from_edges` and
+//! `Graph::par_labels`. These run on scoped threads of the standard library
+//! rather than on a rayon thread pool, so that the crate does not depend on
+//! rayon: every call spawns its own threads, one for each share of the work.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use error::WufError;
+#[cfg(feature = "parallel")]
+use graph::Graph;
+#[cfg(feature = "parallel")]
+use index::Index;
+use rng;
+use union_find::UnionFind;
+
+// default number of stripes of a StripedGraph
+const DEFAULT_STRIPES: usize = 64;
+
+/// Lock-free union-find, whose parents are atomic words updated with
+/// compare-and-swap, so that every operation takes `&self` and no thread ever
+/// waits for another one holding a lock.
+/// 
+/// Roots are linked by randomized priorities (see `RandomizedGraph`), since
+/// sizes and ranks cannot be updated atomically together with the parent, and
+/// paths are compressed by halving, where a failed compare-and-swap is simply
+/// skipped, since another thread already shortened the path.
+/// 
+/// # Example
+/// ```
+/// use std::thread;
+/// use wuf::concurrent::AtomicGraph;
+/// 
+/// let graph = AtomicGraph::new(100);
+/// thread::scope(|scope| {
+///     for t in 0..4 {
+///         let graph = &graph;
+///         scope.spawn(move || {
+///             for id in (t..99).step_by(4) {
+///                 graph.connect(id, id + 1);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(1, graph.component_count());
+/// ```
+#[derive(Debug)]
+pub struct AtomicGraph {
+    nodes: Vec<AtomicUsize>,    // parent of each node
+    seed: u64,                  // seed the priorities are derived from
+    components: AtomicUsize     // number of disjoint components
+}
+
+impl AtomicGraph {
+
+    /// Returns a new AtomicGraph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> AtomicGraph {
+        AtomicGraph {
+            nodes: (0..n).map(AtomicUsize::new).collect(),
+            seed: rng::random_seed(),
+            components: AtomicUsize::new(n)
+        }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time, which is
+    /// exact only when no other thread is connecting nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components.load(Ordering::Acquire)
+    }
+
+    /// Returns true only if the two given nodes are connected, otherwise
+    /// returns false, as of some point during the call.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        let mut a_root = a;
+        let mut b_root = b;
+        loop {
+            a_root = self.find(a_root);
+            b_root = self.find(b_root);
+            if a_root == b_root {
+                return true;
+            }
+            // the roots are distinct at the same time only if the first one
+            // is still a root once the second one is found
+            if self.parent(a_root) == a_root {
+                return false;
+            }
+        }
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&self, a: usize, b: usize) -> Option<usize> {
+        let mut a_root = a;
+        let mut b_root = b;
+        loop {
+            a_root = self.find(a_root);
+            b_root = self.find(b_root);
+            if a_root == b_root {
+                return None;
+            }
+            // link the root of lower priority below the root of higher
+            // priority, breaking ties by ID
+            let (root, child) = if (self.priority(a_root), a_root) < (self.priority(b_root), b_root) {
+                (b_root, a_root)
+            } else {
+                (a_root, b_root)
+            };
+            // fails if another thread linked the child in the meantime
+            let linked = self.nodes[child]
+                .compare_exchange(child, root, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok();
+            if linked {
+                self.components.fetch_sub(1, Ordering::AcqRel);
+                return Some(root);
+            }
+        }
+    }
+
+    /// Returns the root of the given node, which may no longer be a root
+    /// once returned if another thread is connecting nodes.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::AtomicGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        if id >= self.count() {
+            panic!("{}", WufError::OutOfBounds { id, count: self.count() });
+        }
+        let mut node = id;
+        loop {
+            let parent = self.parent(node);
+            let grandparent = self.parent(parent);
+            if parent == grandparent {
+                return parent;
+            }
+            // make every other node in path point to its grandparent, unless
+            // another thread changed its parent in the meantime
+            let _ = self.nodes[node].compare_exchange_weak(
+                parent,
+                grandparent,
+                Ordering::AcqRel,
+                Ordering::Relaxed
+            );
+            node = grandparent;
+        }
+    }
+
+    /// Returns the priority of the given node.
+    fn priority(&self, id: usize) -> u64 {
+        rng::mix(self.seed ^ id as u64)
+    }
+
+    /// Returns the parent of the given node.
+    fn parent(&self, id: usize) -> usize {
+        self.nodes[id].load(Ordering::Acquire)
+    }
+}
+
+impl UnionFind for AtomicGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        AtomicGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        AtomicGraph::connected(self, a, b)
+    }
+}
+
+/// Concurrent union-find whose nodes are split into stripes, each guarded by
+/// a mutex, so that threads working on nodes of different stripes rarely
+/// contend, while every operation remains easy to verify.
+/// 
+/// Node `id` belongs to the stripe `id % stripes`. Roots are linked by size,
+/// holding the locks of the stripes of both roots (acquired in order of
+/// stripe to avoid deadlocks) after checking that they are still roots, while
+/// paths are compressed by halving one node at a time, which is always safe
+/// since a node never loses an ancestor.
+/// 
+/// # Example
+/// ```
+/// use std::thread;
+/// use wuf::concurrent::StripedGraph;
+/// 
+/// let graph = StripedGraph::new(100);
+/// thread::scope(|scope| {
+///     for t in 0..4 {
+///         let graph = &graph;
+///         scope.spawn(move || {
+///             for id in (t..99).step_by(4) {
+///                 graph.connect(id, id + 1);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(1, graph.component_count());
+/// ```
+#[derive(Debug)]
+pub struct StripedGraph {
+    stripes: Vec<Mutex<Stripe>>, // nodes of each stripe
+    count: usize,               // number of nodes
+    components: AtomicUsize     // number of disjoint components
+}
+
+/// Nodes of a stripe, where the node `id` is at index `id / stripes`.
+#[derive(Debug)]
+struct Stripe {
+    parents: Vec<usize>,    // parent of each node
+    sizes: Vec<usize>       // size of the tree rooted at each node
+}
+
+/// The locked stripes of two roots being linked, which may be the same.
+struct Locked<'a> {
+    low: usize,                                 // lower of the two stripes
+    first: MutexGuard<'a, Stripe>,              // lock of the lower stripe
+    second: Option<MutexGuard<'a, Stripe>>      // lock of the higher stripe
+}
+
+impl<'a> Locked<'a> {
+    /// Returns the given locked stripe.
+    fn stripe(&mut self, stripe: usize) -> &mut Stripe {
+        match self.second {
+            Some(ref mut second) if stripe != self.low => second,
+            _ => &mut self.first
+        }
+    }
+}
+
+impl StripedGraph {
+
+    /// Returns a new StripedGraph with the given number of nodes, split into
+    /// a default number of stripes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> StripedGraph {
+        StripedGraph::with_stripes(n, DEFAULT_STRIPES)
+    }
+
+    /// Returns a new StripedGraph with the given number of nodes, split into
+    /// the given number of stripes: more stripes reduce contention at the
+    /// cost of more mutexes.
+    /// 
+    /// Panics if the number of stripes is 0.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `stripes` Number of stripes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::with_stripes(10, 4);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn with_stripes(n: usize, stripes: usize) -> StripedGraph {
+        assert!(stripes > 0, "the number of stripes must be positive");
+        let stripes = (0..stripes).map(|stripe| {
+            let parents: Vec<usize> = (stripe..n).step_by(stripes).collect();
+            let sizes = vec![1; parents.len()];
+            Mutex::new(Stripe { parents, sizes })
+        }).collect();
+        StripedGraph { stripes, count: n, components: AtomicUsize::new(n) }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the number of disjoint components, in constant time, which is
+    /// exact only when no other thread is connecting nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components.load(Ordering::Acquire)
+    }
+
+    /// Returns true only if the two given nodes are connected, otherwise
+    /// returns false, as of some point during the call.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        let mut a_root = a;
+        let mut b_root = b;
+        loop {
+            a_root = self.find(a_root);
+            b_root = self.find(b_root);
+            if a_root == b_root {
+                return true;
+            }
+            // the roots are distinct at the same time only if the first one
+            // is still a root once the second one is found
+            if self.parent(a_root) == a_root {
+                return false;
+            }
+        }
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&self, a: usize, b: usize) -> Option<usize> {
+        let n = self.stripes.len();
+        let mut a_root = a;
+        let mut b_root = b;
+        loop {
+            a_root = self.find(a_root);
+            b_root = self.find(b_root);
+            if a_root == b_root {
+                return None;
+            }
+            let mut locked = self.lock_pair(a_root % n, b_root % n);
+            let a_size = locked.stripe(a_root % n).sizes[a_root / n];
+            let b_size = locked.stripe(b_root % n).sizes[b_root / n];
+            // retry if another thread linked either root in the meantime
+            if locked.stripe(a_root % n).parents[a_root / n] != a_root
+                || locked.stripe(b_root % n).parents[b_root / n] != b_root
+            {
+                continue;
+            }
+            // link root of smaller tree to root of larger tree
+            let (root, child) = if a_size < b_size {
+                (b_root, a_root)
+            } else {
+                (a_root, b_root)
+            };
+            locked.stripe(child % n).parents[child / n] = root;
+            locked.stripe(root % n).sizes[root / n] = a_size + b_size;
+            self.components.fetch_sub(1, Ordering::AcqRel);
+            return Some(root);
+        }
+    }
+
+    /// Returns the root of the given node, which may no longer be a root
+    /// once returned if another thread is connecting nodes.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::concurrent::StripedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        if id >= self.count {
+            panic!("{}", WufError::OutOfBounds { id, count: self.count });
+        }
+        let n = self.stripes.len();
+        let mut node = id;
+        loop {
+            let parent = self.parent(node);
+            if parent == node {
+                return node;
+            }
+            let grandparent = self.parent(parent);
+            // make every other node in path point to its grandparent, unless
+            // another thread changed its parent in the meantime
+            let mut stripe = self.lock(node % n);
+            if stripe.parents[node / n] == parent {
+                stripe.parents[node / n] = grandparent;
+            }
+            node = grandparent;
+        }
+    }
+
+    /// Returns the parent of the given node.
+    fn parent(&self, id: usize) -> usize {
+        let n = self.stripes.len();
+        self.lock(id % n).parents[id / n]
+    }
+
+    /// Locks the given stripe.
+    fn lock(&self, stripe: usize) -> MutexGuard<'_, Stripe> {
+        // the nodes are consistent even if a thread panicked holding the lock
+        self.stripes[stripe].lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the two given stripes in order, and only once if they are the
+    /// same stripe.
+    fn lock_pair(&self, a: usize, b: usize) -> Locked<'_> {
+        let (low, high) = (a.min(b), a.max(b));
+        let first = self.lock(low);
+        let second = if low == high { None } else { Some(self.lock(high)) };
+        Locked { low, first, second }
+    }
+}
+
+impl UnionFind for StripedGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        StripedGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        StripedGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl Graph {
+
+    /// Returns a new Graph with the given number of nodes, connected by the
+    /// given edges, which are split among as many threads as the available
+    /// parallelism.
+    /// 
+    /// Panics if an edge refers to a node out of bounds.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::par_from_edges(5, &[(0, 1), (3, 4)]);
+    /// assert_eq!(vec![vec![0, 1], vec![2], vec![3, 4]], graph.into_sets());
+    /// ```
+    pub fn par_from_edges(n: usize, edges: &[(usize, usize)]) -> Graph {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        Graph::par_from_edges_with_threads(n, edges, threads)
+    }
+
+    /// Returns a new Graph with the given number of nodes, connected by the
+    /// given edges, which are split among the given number of threads.
+    /// 
+    /// The threads connect their edges at the same time in an `AtomicGraph`,
+    /// whose components are then copied into the graph in time linear in the
+    /// number of nodes.
+    /// 
+    /// Panics if an edge refers to a node out of bounds.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// * `threads` Number of threads, where 0 is treated as 1.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::par_from_edges_with_threads(5, &[(0, 1), (3, 4)], 2);
+    /// assert_eq!(3, graph.component_count());
+    /// ```
+    pub fn par_from_edges_with_threads(n: usize, edges: &[(usize, usize)], threads: usize) -> Graph {
+        if let Some(&(a, b)) = edges.iter().find(|&&(a, b)| a >= n || b >= n) {
+            panic!("{}", WufError::OutOfBounds { id: a.max(b), count: n });
+        }
+        let shared = AtomicGraph::new(n);
+        let chunk_size = edges.len().div_ceil(threads.max(1)).max(1);
+        thread::scope(|scope| {
+            for chunk in edges.chunks(chunk_size) {
+                let shared = &shared;
+                scope.spawn(move || {
+                    for &(a, b) in chunk {
+                        shared.connect(a, b);
+                    }
+                });
+            }
+        });
+        let mut graph = Graph::new(n);
+        for id in 0..n {
+            graph.connect(id, shared.find(id));
+        }
+        graph
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<I: Index> Graph<I> {
+
+    /// Same as `labels`, but the roots are found by as many threads as the
+    /// available parallelism.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::from_edges(5, vec![(4, 1), (3, 0)]);
+    /// assert_eq!(vec![0, 1, 2, 0, 1], graph.par_labels());
+    /// ```
+    pub fn par_labels(&self) -> Vec<usize> {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        self.par_labels_with_threads(threads)
+    }
+
+    /// Same as `labels`, but the roots are found by the given number of
+    /// threads, where 0 is treated as 1, while the components are labeled
+    /// sequentially.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::from_edges(5, vec![(4, 1), (3, 0)]);
+    /// assert_eq!(graph.labels(), graph.par_labels_with_threads(2));
+    /// ```
+    pub fn par_labels_with_threads(&self, threads: usize) -> Vec<usize> {
+        // retired nodes are left unlabeled
+        const UNLABELED: usize = usize::MAX;
+        let n = self.count();
+        let chunk_size = n.div_ceil(threads.max(1)).max(1);
+        let mut labels = vec![0; n];
+        thread::scope(|scope| {
+            for (i, chunk) in labels.chunks_mut(chunk_size).enumerate() {
+                scope.spawn(move || {
+                    for (offset, root) in chunk.iter_mut().enumerate() {
+                        let id = i * chunk_size + offset;
+                        *root = if self.is_retired(id) { UNLABELED } else { self.find_ref(id) };
+                    }
+                });
+            }
+        });
+        // label the roots in order of first appearance
+        let mut label_of_root = vec![UNLABELED; n];
+        let mut next = 0;
+        for &root in labels.iter().filter(|&&root| root != UNLABELED) {
+            if label_of_root[root] == UNLABELED {
+                label_of_root[root] = next;
+                next += 1;
+            }
+        }
+        for label in labels.iter_mut().filter(|label| **label != UNLABELED) {
+            *label = label_of_root[*label];
+        }
+        labels
+    }
+
+    /// Calls the given function with the members of every component (see
+    /// `into_sets`), from as many scoped threads as the available parallelism.
+    /// 
+    /// This is not a parallel iterator (as rayon would offer): the components
+    /// are grouped sequentially first, in time linear in the number of nodes,
+    /// and only the calls are split among the threads (see
+    /// `scoped_for_each_component_with_threads`).
+    /// 
+    /// # Arguments
+    /// * `f` The function to call with the members of every component.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// 
+    /// let graph = wuf::Graph::from_edges(5, vec![(4, 1), (3, 0)]);
+    /// let pairs = AtomicUsize::new(0);
+    /// graph.scoped_for_each_component(|members| {
+    ///     pairs.fetch_add(members.len() * (members.len() - 1) / 2, Ordering::Relaxed);
+    /// });
+    /// assert_eq!(2, pairs.into_inner());
+    /// ```
+    pub fn scoped_for_each_component<F: Fn(&[usize]) + Sync>(&self, f: F) {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        self.scoped_for_each_component_with_threads(threads, f)
+    }
+
+    /// Calls the given function with the members of every component (see
+    /// `into_sets`), from the given number of scoped threads, where 0 is
+    /// treated as 1, each calling it for a contiguous share of the components
+    /// once they are all grouped.
+    /// 
+    /// # Arguments
+    /// * `threads` Number of threads.
+    /// * `f` The function to call with the members of every component.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::sync::Mutex;
+    /// 
+    /// let graph = wuf::Graph::from_edges(5, vec![(4, 1), (3, 0)]);
+    /// let sizes = Mutex::new(Vec::new());
+    /// graph.scoped_for_each_component_with_threads(2, |members| {
+    ///     sizes.lock().unwrap().push(members.len());
+    /// });
+    /// let mut sizes = sizes.into_inner().unwrap();
+    /// sizes.sort();
+    /// assert_eq!(vec![1, 2, 2], sizes);
+    /// ```
+    pub fn scoped_for_each_component_with_threads<F: Fn(&[usize]) + Sync>(&self, threads: usize, f: F) {
+        let groups = self.groups();
+        let chunk_size = groups.len().div_ceil(threads.max(1)).max(1);
+        thread::scope(|scope| {
+            for chunk in groups.chunks(chunk_size) {
+                let f = &f;
+                scope.spawn(move || {
+                    for group in chunk {
+                        f(group);
+                    }
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    use graph::Graph;
+    use rng::SplitMix64;
+
+    /// Connects the given edges from several threads using the given
+    /// function, and returns the number of merges.
+    fn connect_concurrently<F: Fn(usize, usize) -> bool + Sync>(edges: &[(usize, usize)], connect: F) -> usize {
+        let merges = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for chunk in edges.chunks(100) {
+                let (connect, merges) = (&connect, &merges);
+                scope.spawn(move || {
+                    for &(a, b) in chunk {
+                        if connect(a, b) {
+                            merges.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        merges.into_inner()
+    }
+
+    #[test]
+    fn should_connect_concurrently() {
+        let n = 1000;
+        let mut rng = SplitMix64::new(3);
+        let edges: Vec<(usize, usize)> = (0..800).map(|_| (rng.below(n), rng.below(n))).collect();
+        let mut expected = Graph::new(n);
+        let expected_merges = expected.connect_all(edges.iter().cloned());
+
+        let graph = AtomicGraph::new(n);
+        assert_eq!(expected_merges, connect_concurrently(&edges, |a, b| graph.connect(a, b).is_some()));
+        assert_eq!(expected.component_count(), graph.component_count());
+        assert!((0..n).all(|id| graph.connected(id, 0) == expected.connected(id, 0)));
+
+        for &stripes in &[1, 3, DEFAULT_STRIPES] {
+            let graph = StripedGraph::with_stripes(n, stripes);
+            assert_eq!(expected_merges, connect_concurrently(&edges, |a, b| graph.connect(a, b).is_some()));
+            assert_eq!(expected.component_count(), graph.component_count());
+            assert!((0..n).all(|id| graph.connected(id, 0) == expected.connected(id, 0)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "node 10 is out of bounds for a graph of 10 nodes")]
+    fn should_panic_out_of_bounds() {
+        AtomicGraph::new(10).connect(0, 10);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn should_build_in_parallel() {
+        let n = 1000;
+        let mut rng = SplitMix64::new(3);
+        let edges: Vec<(usize, usize)> = (0..800).map(|_| (rng.below(n), rng.below(n))).collect();
+        let expected = Graph::from_edges(n, edges.iter().cloned());
+        for &threads in &[0, 1, 3, 1000] {
+            assert!(expected.same_partition(&Graph::par_from_edges_with_threads(n, &edges, threads)));
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn should_process_components_in_parallel() {
+        let n = 1000;
+        let mut rng = SplitMix64::new(3);
+        let edges: Vec<(usize, usize)> = (0..800).map(|_| (rng.below(n), rng.below(n))).collect();
+        let graph = Graph::from_edges(n, edges);
+        let labels = graph.labels();
+        for &threads in &[0, 1, 3, 1000] {
+            assert_eq!(labels, graph.par_labels_with_threads(threads));
+            let members = AtomicUsize::new(0);
+            graph.scoped_for_each_component_with_threads(threads, |group| {
+                assert!(group.iter().all(|&id| labels[id] == labels[group[0]]));
+                members.fetch_add(group.len(), Ordering::Relaxed);
+            });
+            assert_eq!(n, members.into_inner());
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Congruence closure over terms built from function symbols.
+//! 
+//! Terms are applications `f(a, b, ...)` of a symbol to argument terms (or
+//! constants, without arguments), and equalities between terms are closed
+//! under congruence: if `a = c` and `b = d`, then `f(a, b) = f(c, d)`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use graph::Graph;
+
+/// Congruence closure engine, where terms are interned (hash-consed) and
+/// identified by the order in which they were added.
+/// 
+/// Every class of equal terms records the applications using one of its
+/// terms as argument, and a table maps the signature of every application
+/// (its symbol and the classes of its arguments) to an application with that
+/// signature, so that merging two classes finds the congruent applications
+/// by looking up the updated signatures of their uses.
+/// 
+/// # Example
+/// ```
+/// let mut closure = wuf::congruence::CongruenceClosure::new();
+/// let (a, b) = (closure.add_term("a", &[]), closure.add_term("b", &[]));
+/// let fa = closure.add_term("f", &[a]);
+/// let fb = closure.add_term("f", &[b]);
+/// let gfa = closure.add_term("g", &[fa, b]);
+/// let gfb = closure.add_term("g", &[fb, a]);
+/// assert!(!closure.equal(gfa, gfb));
+/// closure.merge(a, b);
+/// assert!(closure.equal(fa, fb));
+/// assert!(closure.equal(gfa, gfb));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CongruenceClosure<S: Hash + Eq> {
+    graph: Graph,                                   // classes of equal terms
+    terms: HashMap<(S, Vec<usize>), usize>,         // ID of each interned term
+    signatures: HashMap<(S, Vec<usize>), usize>,    // application of each signature
+    applications: Vec<(S, Vec<usize>)>,             // symbol and arguments of each term
+    uses: Vec<Vec<usize>>                           // applications using each class
+}
+
+impl<S: Hash + Eq + Clone> CongruenceClosure<S> {
+
+    /// Returns a new CongruenceClosure without terms.
+    pub fn new() -> CongruenceClosure<S> {
+        CongruenceClosure {
+            graph: Graph::new(0),
+            terms: HashMap::new(),
+            signatures: HashMap::new(),
+            applications: Vec::new(),
+            uses: Vec::new()
+        }
+    }
+
+    /// Returns the number of terms.
+    pub fn count(&self) -> usize {
+        self.applications.len()
+    }
+
+    /// Returns the number of classes of equal terms.
+    pub fn class_count(&self) -> usize {
+        self.graph.component_count()
+    }
+
+    /// Adds the application of the given symbol to the given arguments (a
+    /// constant if there are no arguments), and returns its ID, which is the
+    /// ID of the same term if already added.
+    /// 
+    /// The term is merged with every congruent term already added.
+    /// 
+    /// Panics if an argument is not a term.
+    /// 
+    /// # Arguments
+    /// * `symbol` The function symbol.
+    /// * `args` The IDs of the arguments.
+    pub fn add_term(&mut self, symbol: S, args: &[usize]) -> usize {
+        let key = (symbol, args.to_vec());
+        if let Some(&id) = self.terms.get(&key) {
+            return id;
+        }
+        let id = self.graph.add_nodes(1).start;
+        self.terms.insert(key.clone(), id);
+        self.applications.push(key);
+        self.uses.push(Vec::new());
+        for &arg in args {
+            let root = self.graph.find(arg);
+            self.uses[root].push(id);
+        }
+        let signature = self.signature(id);
+        match self.signatures.get(&signature).cloned() {
+            Some(congruent) => self.merge(id, congruent),
+            None => {
+                self.signatures.insert(signature, id);
+            }
+        }
+        id
+    }
+
+    /// Returns true only if the two given terms are equal.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first term.
+    /// * `b` ID of the second term.
+    pub fn equal(&mut self, a: usize, b: usize) -> bool {
+        self.graph.connected(a, b)
+    }
+
+    /// Returns the representative of the class of the given term.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the term.
+    pub fn find(&mut self, id: usize) -> usize {
+        self.graph.find(id)
+    }
+
+    /// Asserts that the two given terms are equal, and merges every pair of
+    /// terms that become congruent as a consequence.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first term.
+    /// * `b` ID of the second term.
+    pub fn merge(&mut self, a: usize, b: usize) {
+        let mut pending = vec![(a, b)];
+        while let Some((a, b)) = pending.pop() {
+            let a_root = self.graph.find(a);
+            let b_root = self.graph.find(b);
+            let root = match self.graph.connect(a_root, b_root) {
+                Some(root) => root,
+                None => continue
+            };
+            let child = if root == a_root { b_root } else { a_root };
+            let uses = ::std::mem::take(&mut self.uses[child]);
+            // the signatures of the uses of the merged class have changed
+            for &id in &uses {
+                let signature = self.signature(id);
+                match self.signatures.get(&signature).cloned() {
+                    Some(congruent) => pending.push((id, congruent)),
+                    None => {
+                        self.signatures.insert(signature, id);
+                    }
+                }
+            }
+            self.uses[root].extend(uses);
+        }
+    }
+
+    /// Returns the symbol of the given application and the classes of its
+    /// arguments.
+    fn signature(&mut self, id: usize) -> (S, Vec<usize>) {
+        let (ref symbol, ref args) = self.applications[id];
+        let graph = &mut self.graph;
+        (symbol.clone(), args.iter().map(|&arg| graph.find(arg)).collect())
+    }
+}
+
+impl<S: Hash + Eq + Clone> Default for CongruenceClosure<S> {
+    fn default() -> Self {
+        CongruenceClosure::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_close_under_congruence() {
+        // f(f(f(a))) = a and f(f(f(f(f(a))))) = a imply f(a) = a
+        let mut closure = CongruenceClosure::new();
+        let mut terms = vec![closure.add_term('a', &[])];
+        for i in 0..5 {
+            let term = closure.add_term('f', &[terms[i]]);
+            terms.push(term);
+        }
+        assert_eq!(terms[3], closure.add_term('f', &[terms[2]]));
+        closure.merge(terms[3], terms[0]);
+        assert!(!closure.equal(terms[1], terms[0]));
+        assert_eq!(3, closure.class_count());
+        closure.merge(terms[5], terms[0]);
+        assert!(closure.equal(terms[1], terms[0]));
+        assert_eq!(1, closure.class_count());
+        // terms added later are merged with the congruent terms
+        let b = closure.add_term('b', &[]);
+        let gb = closure.add_term('g', &[b, terms[4]]);
+        closure.merge(b, terms[2]);
+        let ga = closure.add_term('g', &[terms[0], terms[0]]);
+        assert!(closure.equal(ga, gb));
+        assert_eq!(9, closure.count());
+    }
+}
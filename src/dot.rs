@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use graph::Graph;
+use index::Index;
+
+impl<I: Index> Graph<I> {
+
+    /// Writes the internal forest of the graph in the Graphviz DOT format,
+    /// with an edge from every node to its parent, and every root labeled
+    /// with the size of its tree.
+    /// 
+    /// # Arguments
+    /// * `writer` The writer to write the forest to.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(3);
+    /// graph.connect(0, 1);
+    /// let mut dot = Vec::new();
+    /// graph.write_dot(&mut dot).unwrap();
+    /// assert!(String::from_utf8(dot).unwrap().contains("1 -> 0;"));
+    /// ```
+    pub fn write_dot<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "digraph {{")?;
+        for (id, parent) in self.parents().enumerate() {
+            if id == parent {
+                writeln!(
+                    writer,
+                    "    {} [label=\"{} ({})\", shape=doublecircle];",
+                    id, id, self.size_of(id)
+                )?;
+            } else {
+                writeln!(writer, "    {} -> {};", id, parent)?;
+            }
+        }
+        writeln!(writer, "}}")
+    }
+
+    /// Returns the internal forest of the graph in the Graphviz DOT format
+    /// (see `write_dot`).
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(3);
+    /// graph.connect(0, 1);
+    /// println!("{}", graph.to_dot());
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = Vec::new();
+        self.write_dot(&mut dot).expect("writing to a vector cannot fail");
+        String::from_utf8(dot).expect("DOT output is always valid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_dot() {
+        let mut graph = Graph::new(4);
+        graph.connect(0, 1);
+        graph.connect(2, 1);
+        let expected = "digraph {\n    0 [label=\"0 (3)\", shape=doublecircle];\n    1 -> 0;\n    \
+            2 -> 0;\n    3 [label=\"3 (1)\", shape=doublecircle];\n}\n";
+        assert_eq!(expected, graph.to_dot());
+    }
+}
@@ -0,0 +1,187 @@
+//! Offline fully-dynamic connectivity, where edges can be deleted as well as
+//! inserted.
+
+use std::collections::HashMap;
+
+use rollback::RollbackGraph;
+
+/// Operation on an undirected graph with a fixed number of nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Inserts an edge between the two nodes (multiple edges are allowed).
+    Insert(usize, usize),
+    /// Deletes an edge previously inserted between the two nodes.
+    Delete(usize, usize),
+    /// Queries whether the two nodes are connected.
+    Query(usize, usize)
+}
+
+/// Returns the answer to every query of the given timeline of operations, in
+/// order, as true only if the two nodes were connected at the time of the
+/// query.
+/// 
+/// Every edge is alive during an interval of time, that is split among the
+/// nodes of a segment tree over time; the tree is then visited depth-first,
+/// connecting the edges of a node when entering it and undoing them when
+/// leaving it, so that every query is answered at its leaf. Takes
+/// `O(m log m log n)` time for `m` operations on `n` nodes.
+/// 
+/// Panics if an operation refers to a node out of bounds, or if an edge is
+/// deleted more times than it was inserted.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `operations` Timeline of operations.
+/// 
+/// # Example
+/// ```
+/// use wuf::dynamic::{solve, Operation};
+/// 
+/// let operations = [
+///     Operation::Insert(0, 1),
+///     Operation::Insert(1, 2),
+///     Operation::Query(0, 2),
+///     Operation::Delete(1, 0),
+///     Operation::Query(0, 2)
+/// ];
+/// assert_eq!(vec![true, false], solve(3, &operations));
+/// ```
+pub fn solve(n: usize, operations: &[Operation]) -> Vec<bool> {
+    let m = operations.len();
+    // insertion times of the alive edges, by edge
+    let mut alive: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    let mut intervals = Vec::new();
+    for (time, &operation) in operations.iter().enumerate() {
+        match operation {
+            Operation::Insert(a, b) => {
+                assert!(a < n && b < n, "node out of bounds (count is {})", n);
+                alive.entry(edge(a, b)).or_default().push(time);
+            }
+            Operation::Delete(a, b) => {
+                let start = alive.get_mut(&edge(a, b)).and_then(|times| times.pop())
+                    .unwrap_or_else(|| panic!("edge ({}, {}) deleted but not inserted", a, b));
+                intervals.push((start, time, edge(a, b)));
+            }
+            Operation::Query(a, b) => {
+                assert!(a < n && b < n, "node out of bounds (count is {})", n);
+            }
+        }
+    }
+    // edges never deleted are alive until the end of the timeline
+    for (&edge, times) in &alive {
+        intervals.extend(times.iter().map(|&start| (start, m, edge)));
+    }
+
+    let mut tree = vec![Vec::new(); 2 * m.next_power_of_two()];
+    for (start, end, edge) in intervals {
+        insert(&mut tree, 1, 0, m, start, end, edge);
+    }
+    let mut graph = RollbackGraph::new(n);
+    let mut answers = Vec::new();
+    visit(&tree, 1, 0, m, operations, &mut graph, &mut answers);
+    answers
+}
+
+/// Returns the given edge with the lowest node first.
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Adds the edge alive in the interval `[start, end)` to the nodes of the
+/// segment tree covering it, where the given node covers `[low, high)`.
+fn insert(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize)
+) {
+    if end <= low || high <= start {
+        return;
+    }
+    if start <= low && high <= end {
+        tree[node].push(edge);
+        return;
+    }
+    let middle = (low + high) / 2;
+    insert(tree, 2 * node, low, middle, start, end, edge);
+    insert(tree, 2 * node + 1, middle, high, start, end, edge);
+}
+
+/// Visits the given node of the segment tree, covering `[low, high)`, with the
+/// edges of its ancestors connected, answering the queries of its leaves.
+fn visit(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    operations: &[Operation],
+    graph: &mut RollbackGraph,
+    answers: &mut Vec<bool>
+) {
+    if low >= high {
+        return;
+    }
+    let unions = tree[node].iter().filter(|&&(a, b)| graph.connect(a, b)).count();
+    if high - low == 1 {
+        if let Operation::Query(a, b) = operations[low] {
+            answers.push(graph.connected(a, b));
+        }
+    } else {
+        let middle = (low + high) / 2;
+        visit(tree, 2 * node, low, middle, operations, graph, answers);
+        visit(tree, 2 * node + 1, middle, high, operations, graph, answers);
+    }
+    graph.undo(unions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::Graph;
+
+    #[test]
+    fn should_match_recomputation() {
+        let n = 12;
+        let mut operations = Vec::new();
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for i in 0..400u64 {
+            let hash = ::rng::mix(i);
+            let (a, b) = ((hash % n) as usize, ((hash >> 8) % n) as usize);
+            match (hash >> 16) % 3 {
+                0 => {
+                    operations.push(Operation::Insert(a, b));
+                    edges.push((a, b));
+                }
+                1 if !edges.is_empty() => {
+                    let (a, b) = edges.swap_remove((hash >> 24) as usize % edges.len());
+                    operations.push(Operation::Delete(b, a));
+                }
+                _ => operations.push(Operation::Query(a, b))
+            }
+        }
+        // recompute the connectivity from scratch for every query
+        let mut expected = Vec::new();
+        let mut alive: Vec<(usize, usize)> = Vec::new();
+        for &operation in &operations {
+            match operation {
+                Operation::Insert(a, b) => alive.push(edge(a, b)),
+                Operation::Delete(a, b) => {
+                    let position = alive.iter().position(|&e| e == edge(a, b)).unwrap();
+                    alive.swap_remove(position);
+                }
+                Operation::Query(a, b) => {
+                    let mut graph = Graph::new(n as usize);
+                    for &(u, v) in &alive {
+                        graph.connect(u, v);
+                    }
+                    expected.push(graph.connected(a, b));
+                }
+            }
+        }
+        assert_eq!(expected, solve(n as usize, &operations));
+        assert!(solve(0, &[]).is_empty());
+    }
+}
@@ -0,0 +1,326 @@
+use std::io::{self, BufRead};
+
+use graph::Graph;
+
+// number of nodes a file can declare when read without an explicit limit
+const DEFAULT_LIMIT: usize = 1 << 26;
+
+impl Graph {
+
+    /// Returns a new Graph with the given number of nodes, connected by the
+    /// edges read from the given reader.
+    /// 
+    /// Every line holds an edge as a pair of node IDs separated by whitespace
+    /// or by a comma, while empty lines and lines starting with `#` are
+    /// skipped. Returns an error of kind `InvalidData`, referring to the line
+    /// number, if a line is malformed or refers to a node out of bounds.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `reader` The reader to read the edges from.
+    /// 
+    /// # Example
+    /// ```
+    /// let edges = "# sample\n0 1\n1,2\n\n3\t4\n";
+    /// let mut graph = wuf::Graph::from_edge_reader(5, edges.as_bytes()).unwrap();
+    /// assert!(graph.connected(0, 2));
+    /// assert_eq!(2, graph.component_count());
+    /// ```
+    pub fn from_edge_reader<R: BufRead>(n: usize, reader: R) -> io::Result<Graph> {
+        let mut graph = Graph::new(n);
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let edge = parse_edge(&line).and_then(|edge| match edge {
+                Some((a, b)) if a >= n || b >= n => Err(format!(
+                    "node {} out of bounds (count is {})",
+                    a.max(b), n
+                )),
+                edge => Ok(edge)
+            });
+            match edge {
+                Ok(Some((a, b))) => {
+                    graph.connect(a, b);
+                }
+                Ok(None) => {}
+                Err(message) => return Err(invalid_line(i, &message))
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Returns a new Graph read from the given reader in the DIMACS format.
+    /// 
+    /// Lines starting with `c` are comments, and the problem line
+    /// `p FORMAT NODES EDGES` must precede every edge, given either as
+    /// `e U V` or as an arc `a U V WEIGHT` (whose weight is ignored), with node
+    /// IDs starting from 1. Returns an error of kind `InvalidData`, referring
+    /// to the line number, if a line is malformed or refers to a node out of
+    /// bounds, or if the problem line declares more than 2^26 nodes (see
+    /// `from_dimacs_reader_with_limit`).
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the graph from.
+    /// 
+    /// # Example
+    /// ```
+    /// let dimacs = "c sample\np edge 4 2\ne 1 2\ne 2 3\n";
+    /// let mut graph = wuf::Graph::from_dimacs_reader(dimacs.as_bytes()).unwrap();
+    /// assert_eq!(4, graph.count());
+    /// assert!(graph.connected(0, 2));
+    /// ```
+    pub fn from_dimacs_reader<R: BufRead>(reader: R) -> io::Result<Graph> {
+        Graph::from_dimacs_reader_with_limit(reader, DEFAULT_LIMIT)
+    }
+
+    /// Same as `from_dimacs_reader`, but accepts up to the given number of
+    /// nodes, since the nodes declared by the problem line are allocated
+    /// before reading any edge.
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the graph from.
+    /// * `max_nodes` Maximum number of nodes the problem line can declare.
+    /// 
+    /// # Example
+    /// ```
+    /// let dimacs = "p edge 1000 0\n";
+    /// assert!(wuf::Graph::from_dimacs_reader_with_limit(dimacs.as_bytes(), 100).is_err());
+    /// ```
+    pub fn from_dimacs_reader_with_limit<R: BufRead>(reader: R, max_nodes: usize) -> io::Result<Graph> {
+        let mut graph = None;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                None | Some("c") => {}
+                Some("p") if graph.is_some() => {
+                    return Err(invalid_line(i, "duplicate problem line"));
+                }
+                Some("p") => {
+                    let n = fields.nth(1).ok_or_else(|| "expected the number of nodes".to_string())
+                        .and_then(|field| parse_count(field, max_nodes))
+                        .map_err(|message| invalid_line(i, &message))?;
+                    graph = Some(Graph::new(n));
+                }
+                Some(kind @ "e") | Some(kind @ "a") => {
+                    let graph = graph.as_mut()
+                        .ok_or_else(|| invalid_line(i, "edge before the problem line"))?;
+                    let arity = if kind == "e" { 2 } else { 3 };
+                    let fields: Vec<&str> = fields.collect();
+                    if fields.len() != arity {
+                        let message = format!("expected {} fields after `{}`", arity, kind);
+                        return Err(invalid_line(i, &message));
+                    }
+                    let (a, b) = parse_one_based(fields[0], fields[1], graph.count())
+                        .map_err(|message| invalid_line(i, &message))?;
+                    graph.connect(a, b);
+                }
+                Some(field) => {
+                    let message = format!("unknown line type `{}`", field);
+                    return Err(invalid_line(i, &message));
+                }
+            }
+        }
+        graph.ok_or_else(|| invalid_data("missing problem line"))
+    }
+
+    /// Returns a new Graph read from the given reader in the Matrix Market
+    /// coordinate format, connecting the row and the column of every entry.
+    /// 
+    /// The banner `%%MatrixMarket matrix coordinate ...` must be followed by
+    /// optional comments starting with `%`, by the size line `ROWS COLUMNS
+    /// ENTRIES` of a square matrix, and by the entries `ROW COLUMN [VALUE]`,
+    /// with indices starting from 1 and values ignored. Returns an error of
+    /// kind `InvalidData`, referring to the line number, if a line is
+    /// malformed or refers to a node out of bounds, or if the size line
+    /// declares more than 2^26 nodes (see
+    /// `from_matrix_market_reader_with_limit`).
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the graph from.
+    /// 
+    /// # Example
+    /// ```
+    /// let mtx = "%%MatrixMarket matrix coordinate real symmetric\n3 3 2\n1 2 0.5\n3 3 1.0\n";
+    /// let mut graph = wuf::Graph::from_matrix_market_reader(mtx.as_bytes()).unwrap();
+    /// assert!(graph.connected(0, 1));
+    /// assert_eq!(2, graph.component_count());
+    /// ```
+    pub fn from_matrix_market_reader<R: BufRead>(reader: R) -> io::Result<Graph> {
+        Graph::from_matrix_market_reader_with_limit(reader, DEFAULT_LIMIT)
+    }
+
+    /// Same as `from_matrix_market_reader`, but accepts up to the given
+    /// number of nodes, since the nodes declared by the size line are
+    /// allocated before reading any entry.
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the graph from.
+    /// * `max_nodes` Maximum number of nodes the size line can declare.
+    pub fn from_matrix_market_reader_with_limit<R: BufRead>(
+        reader: R,
+        max_nodes: usize
+    ) -> io::Result<Graph> {
+        let mut lines = reader.lines().enumerate();
+        let banner = match lines.next() {
+            Some((_, line)) => line?,
+            None => return Err(invalid_data("missing Matrix Market banner"))
+        };
+        let banner: Vec<String> = banner.split_whitespace().map(str::to_lowercase).collect();
+        if banner.len() < 3 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" {
+            return Err(invalid_line(0, "expected a Matrix Market banner"));
+        }
+        if banner[2] != "coordinate" {
+            return Err(invalid_line(0, "expected a matrix in coordinate format"));
+        }
+        let mut graph = None;
+        for (i, line) in lines {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('%') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match graph {
+                None => {
+                    if fields.len() != 3 {
+                        return Err(invalid_line(i, "expected the size line"));
+                    }
+                    let rows = parse_count(fields[0], max_nodes).map_err(|m| invalid_line(i, &m))?;
+                    let columns = parse_count(fields[1], max_nodes).map_err(|m| invalid_line(i, &m))?;
+                    if rows != columns {
+                        return Err(invalid_line(i, "expected a square matrix"));
+                    }
+                    graph = Some(Graph::new(rows));
+                }
+                Some(ref mut graph) => {
+                    if fields.len() < 2 {
+                        return Err(invalid_line(i, "expected a row and a column"));
+                    }
+                    let (a, b) = parse_one_based(fields[0], fields[1], graph.count())
+                        .map_err(|message| invalid_line(i, &message))?;
+                    graph.connect(a, b);
+                }
+            }
+        }
+        graph.ok_or_else(|| invalid_data("missing size line"))
+    }
+}
+
+/// Parses a number of nodes, at most the given limit.
+fn parse_count(field: &str, max_nodes: usize) -> Result<usize, String> {
+    let n = field.parse().ok()
+        .filter(|&n| n <= Graph::<usize>::MAX_NODES)
+        .ok_or_else(|| format!("invalid number of nodes `{}`", field))?;
+    if n > max_nodes {
+        return Err(format!("{} nodes exceed the limit of {}", n, max_nodes));
+    }
+    Ok(n)
+}
+
+/// Parses a pair of node IDs starting from 1, and returns them starting from 0.
+fn parse_one_based(a: &str, b: &str, n: usize) -> Result<(usize, usize), String> {
+    let parse = |field: &str| -> Result<usize, String> {
+        let id: usize = field.parse()
+            .map_err(|_| format!("invalid node ID `{}`", field))?;
+        if id == 0 || id > n {
+            return Err(format!("node {} out of bounds (count is {})", id, n));
+        }
+        Ok(id - 1)
+    };
+    Ok((parse(a)?, parse(b)?))
+}
+
+/// Returns a new error of kind `InvalidData` with the given message, referring
+/// to the line with the given (zero-based) index.
+fn invalid_line(i: usize, message: &str) -> io::Error {
+    invalid_data(&format!("line {}: {}", i + 1, message))
+}
+
+/// Returns a new error of kind `InvalidData` with the given message.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Parses an edge as a pair of node IDs separated by whitespace or by a comma.
+/// 
+/// Returns `None` if the line is empty or is a comment starting with `#`.
+pub(crate) fn parse_edge(line: &str) -> Result<Option<(usize, usize)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let mut fields = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty());
+    let mut next = || -> Result<usize, String> {
+        let field = fields.next().ok_or("expected two node IDs")?;
+        field.parse().map_err(|_| format!("invalid node ID `{}`", field))
+    };
+    let a = next()?;
+    let b = next()?;
+    match fields.next() {
+        Some(field) => Err(format!("unexpected field `{}`", field)),
+        None => Ok(Some((a, b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_edges() {
+        assert_eq!(Ok(Some((0, 1))), parse_edge("0 1"));
+        assert_eq!(Ok(Some((2, 3))), parse_edge(" 2 , 3 "));
+        assert_eq!(Ok(None), parse_edge("  "));
+        assert_eq!(Ok(None), parse_edge("# 0 1"));
+        assert!(parse_edge("0").is_err());
+        assert!(parse_edge("0 x").is_err());
+        assert!(parse_edge("0 1 2").is_err());
+    }
+
+    #[test]
+    fn should_report_malformed_line() {
+        let err = Graph::from_edge_reader(3, "0 1\n1 -2\n".as_bytes()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("line 2: invalid node ID `-2`", err.to_string());
+        let err = Graph::from_edge_reader(3, "0 3\n".as_bytes()).unwrap_err();
+        assert_eq!("line 1: node 3 out of bounds (count is 3)", err.to_string());
+    }
+
+    #[test]
+    fn should_read_dimacs() {
+        let dimacs = "c road network\np sp 5 3\na 1 2 7\na 2 1 7\n\na 4 5 1\n";
+        let mut graph = Graph::from_dimacs_reader(dimacs.as_bytes()).unwrap();
+        assert_eq!(5, graph.count());
+        assert_eq!(3, graph.component_count());
+        assert!(graph.connected(3, 4));
+        let err = Graph::from_dimacs_reader("e 1 2\n".as_bytes()).unwrap_err();
+        assert_eq!("line 1: edge before the problem line", err.to_string());
+        let err = Graph::from_dimacs_reader("p edge 2 1\ne 0 1\n".as_bytes()).unwrap_err();
+        assert_eq!("line 2: node 0 out of bounds (count is 2)", err.to_string());
+        let err = Graph::from_dimacs_reader("c empty\n".as_bytes()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        let err = Graph::from_dimacs_reader("p edge 1152921504606846976 0\n".as_bytes()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        let dimacs = "p edge 3 1\ne 1 3\n".as_bytes();
+        assert!(Graph::from_dimacs_reader_with_limit(dimacs, 2).is_err());
+        assert_eq!(3, Graph::from_dimacs_reader_with_limit(dimacs, 3).unwrap().count());
+    }
+
+    #[test]
+    fn should_read_matrix_market() {
+        let mtx = "%%MatrixMarket matrix coordinate pattern general\n% comment\n4 4 2\n1 4\n2 3\n";
+        let mut graph = Graph::from_matrix_market_reader(mtx.as_bytes()).unwrap();
+        assert_eq!(2, graph.component_count());
+        assert!(graph.connected(0, 3));
+        let mtx = "%%MatrixMarket matrix array real general\n2 2\n";
+        assert!(Graph::from_matrix_market_reader(mtx.as_bytes()).is_err());
+        let mtx = "%%MatrixMarket matrix coordinate real general\n2 3 1\n1 3 1.0\n";
+        let err = Graph::from_matrix_market_reader(mtx.as_bytes()).unwrap_err();
+        assert_eq!("line 2: expected a square matrix", err.to_string());
+        let mtx = "%%MatrixMarket matrix coordinate pattern general\n4 4 0\n";
+        let err = Graph::from_matrix_market_reader_with_limit(mtx.as_bytes(), 3).unwrap_err();
+        assert_eq!("line 2: 4 nodes exceed the limit of 3", err.to_string());
+    }
+}
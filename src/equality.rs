@@ -0,0 +1,76 @@
+//! Satisfiability of equality and disequality constraints between variables.
+
+use error::WufError;
+use graph::Graph;
+
+/// Constraint between two variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// The two variables are equal.
+    Equal(usize, usize),
+    /// The two variables are different.
+    NotEqual(usize, usize)
+}
+
+/// Checks whether the given constraints between `n` variables are jointly
+/// satisfiable, that is no disequality relates two variables made equal by
+/// the equalities.
+/// 
+/// Returns `WufError::Contradiction` with the variables of the first violated
+/// disequality (in order of the constraints), or `WufError::OutOfBounds` if a
+/// constraint refers to a variable out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of variables.
+/// * `constraints` The constraints between the variables.
+/// 
+/// # Example
+/// ```
+/// use wuf::equality::{satisfiable, Constraint};
+/// use wuf::WufError;
+/// 
+/// let mut constraints = vec![Constraint::Equal(0, 1), Constraint::NotEqual(1, 2)];
+/// assert_eq!(Ok(()), satisfiable(3, &constraints));
+/// constraints.push(Constraint::Equal(2, 0));
+/// assert_eq!(Err(WufError::Contradiction { a: 1, b: 2 }), satisfiable(3, &constraints));
+/// ```
+pub fn satisfiable(n: usize, constraints: &[Constraint]) -> Result<(), WufError> {
+    let mut graph = Graph::new(n);
+    for &constraint in constraints {
+        let (a, b) = match constraint {
+            Constraint::Equal(a, b) | Constraint::NotEqual(a, b) => (a, b)
+        };
+        graph.try_find(a.max(b))?;
+        if let Constraint::Equal(a, b) = constraint {
+            graph.connect(a, b);
+        }
+    }
+    // disequalities are checked once every equality is known
+    for &constraint in constraints {
+        if let Constraint::NotEqual(a, b) = constraint {
+            if graph.connected(a, b) {
+                return Err(WufError::Contradiction { a, b });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_check_satisfiability() {
+        use self::Constraint::*;
+        assert_eq!(Ok(()), satisfiable(0, &[]));
+        assert_eq!(Err(WufError::Contradiction { a: 2, b: 2 }), satisfiable(3, &[NotEqual(2, 2)]));
+        let constraints = [NotEqual(0, 3), NotEqual(1, 4), Equal(0, 1), Equal(2, 1), Equal(4, 2)];
+        assert_eq!(Err(WufError::Contradiction { a: 1, b: 4 }), satisfiable(5, &constraints));
+        assert_eq!(Ok(()), satisfiable(5, &constraints[..4]));
+        assert_eq!(
+            Err(WufError::OutOfBounds { id: 5, count: 5 }),
+            satisfiable(5, &[Equal(0, 1), NotEqual(5, 1)])
+        );
+    }
+}
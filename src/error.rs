@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::fmt;
+
+/// The error type returned by the fallible operations of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WufError {
+    /// The given node ID is not part of the graph, which has `count` nodes.
+    OutOfBounds { id: usize, count: usize },
+    /// The number of nodes exceeds the `max` supported by the index type.
+    TooManyNodes { count: usize, max: usize },
+    /// The parent of the node `id` is not part of the graph.
+    InvalidParent { id: usize, parent: usize },
+    /// The node `id` belongs to a cycle of parents, hence it has no root.
+    Cycle { id: usize },
+    /// The size of the tree rooted at `id` was expected to be `expected`
+    /// rather than `found`.
+    SizeMismatch { id: usize, expected: usize, found: usize },
+    /// The length of a per-node array (such as sizes or ranks) does not match
+    /// the number of nodes.
+    LengthMismatch { len: usize, count: usize },
+    /// The relation asserted between the nodes `a` and `b` contradicts the
+    /// relations asserted so far.
+    Contradiction { a: usize, b: usize },
+    /// The node `id` appears in more than one of the given components.
+    Overlap { id: usize },
+    /// The rank of the node `id` cannot result from linking by rank, as it
+    /// exceeds the binary logarithm of the size of its tree (for roots), or is
+    /// not lower than the rank of its parent.
+    InvalidRank { id: usize, rank: u8 },
+    /// The node `id` was retired (see `Graph::remove_node`), and not reused
+    /// since.
+    Retired { id: usize },
+}
+
+impl fmt::Display for WufError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WufError::OutOfBounds { id, count } => write!(
+                f,
+                "node {} is out of bounds for a graph of {} nodes",
+                id, count
+            ),
+            WufError::TooManyNodes { count, max } => write!(
+                f,
+                "{} nodes exceed the maximum of {} nodes",
+                count, max
+            ),
+            WufError::InvalidParent { id, parent } => write!(
+                f,
+                "parent {} of node {} is not part of the graph",
+                parent, id
+            ),
+            WufError::Cycle { id } => write!(f, "node {} belongs to a cycle", id),
+            WufError::SizeMismatch { id, expected, found } => write!(
+                f,
+                "tree rooted at node {} has {} nodes rather than {}",
+                id, expected, found
+            ),
+            WufError::LengthMismatch { len, count } => write!(
+                f,
+                "{} entries do not match a graph of {} nodes",
+                len, count
+            ),
+            WufError::Contradiction { a, b } => write!(
+                f,
+                "relation between nodes {} and {} contradicts the previous relations",
+                a, b
+            ),
+            WufError::Overlap { id } => write!(
+                f,
+                "node {} belongs to more than one component",
+                id
+            ),
+            WufError::InvalidRank { id, rank } => write!(
+                f,
+                "rank {} of node {} is not consistent with its tree",
+                rank, id
+            ),
+            WufError::Retired { id } => write!(f, "node {} has been removed", id),
+        }
+    }
+}
+
+impl Error for WufError {}
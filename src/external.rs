@@ -0,0 +1,311 @@
+//! Connected components of edge lists larger than memory.
+//! 
+//! Edges are streamed from disk in fixed-size chunks, so that only the
+//! union-find (a few words per node) lives in memory, however many edges
+//! there are. Edges are stored as pairs of little-endian node IDs of 4 or 8
+//! bytes. Edge lists can also be split across independent jobs, each spilling
+//! the labels of its components with `write_labels`, to be combined by
+//! `merge_labels`.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use graph::Graph;
+
+// default number of edges read at once
+const CHUNK: usize = 1 << 16;
+
+/// Width of the node IDs of the edges in a binary edge list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    /// Node IDs are little-endian integers of 4 bytes.
+    U32,
+    /// Node IDs are little-endian integers of 8 bytes.
+    U64
+}
+
+impl Width {
+    /// Returns the number of bytes of a node ID.
+    fn bytes(self) -> usize {
+        match self {
+            Width::U32 => 4,
+            Width::U64 => 8
+        }
+    }
+}
+
+/// Batch driver connecting the edges streamed from any number of binary edge
+/// lists, reading a chunk of edges at a time.
+/// 
+/// # Example
+/// ```
+/// use wuf::external::{ExternalComponents, Width};
+/// 
+/// let mut bytes = Vec::new();
+/// for id in [0u32, 1, 1, 2, 3, 4].iter() {
+///     bytes.extend_from_slice(&id.to_le_bytes());
+/// }
+/// let mut components = ExternalComponents::new(5);
+/// assert_eq!(3, components.connect_reader(bytes.as_slice(), Width::U32).unwrap());
+/// assert_eq!(3, components.edge_count());
+/// assert_eq!(2, components.graph().component_count());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExternalComponents {
+    graph: Graph,       // components of the edges read so far
+    chunk: usize,       // number of edges read at once
+    edges: u64          // number of edges read so far
+}
+
+impl ExternalComponents {
+
+    /// Returns a new driver for the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    pub fn new(n: usize) -> ExternalComponents {
+        ExternalComponents::with_chunk(n, CHUNK)
+    }
+
+    /// Returns a new driver for the given number of nodes, reading the given
+    /// number of edges at once.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `chunk` Number of edges read at once (at least 1).
+    pub fn with_chunk(n: usize, chunk: usize) -> ExternalComponents {
+        ExternalComponents { graph: Graph::new(n), chunk: chunk.max(1), edges: 0 }
+    }
+
+    /// Returns the number of edges read so far.
+    pub fn edge_count(&self) -> u64 {
+        self.edges
+    }
+
+    /// Returns the components of the edges read so far.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Returns the components of the edges read so far, consuming the driver.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    /// Connects the edges read from the binary edge list at the given path,
+    /// and returns the number of merges.
+    /// 
+    /// # Arguments
+    /// * `path` Path of the edge list.
+    /// * `width` Width of the node IDs.
+    pub fn connect_file<P: AsRef<Path>>(&mut self, path: P, width: Width) -> io::Result<usize> {
+        self.connect_reader(File::open(path)?, width)
+    }
+
+    /// Connects the edges read from the given binary edge list, and returns
+    /// the number of merges.
+    /// 
+    /// Returns an error of kind `InvalidData`, referring to the edge number,
+    /// if an edge refers to a node out of bounds or is truncated, after
+    /// connecting the edges before it.
+    /// 
+    /// # Arguments
+    /// * `reader` The reader to read the edges from.
+    /// * `width` Width of the node IDs.
+    pub fn connect_reader<R: Read>(&mut self, mut reader: R, width: Width) -> io::Result<usize> {
+        let n = self.graph.count();
+        let width = width.bytes();
+        let mut buffer = vec![0; self.chunk * 2 * width];
+        let mut edges = Vec::with_capacity(self.chunk);
+        let mut merges = 0;
+        loop {
+            let len = fill(&mut reader, &mut buffer)?;
+            let complete = len - len % (2 * width);
+            edges.clear();
+            for (i, pair) in buffer[..complete].chunks(2 * width).enumerate() {
+                let (a, b) = (decode(&pair[..width]), decode(&pair[width..]));
+                if a >= n as u64 || b >= n as u64 {
+                    self.graph.connect_all(edges.drain(..));
+                    let message = format!(
+                        "edge {} refers to node {} out of bounds (count is {})",
+                        self.edges + i as u64, a.max(b), n
+                    );
+                    self.edges += i as u64;
+                    return Err(invalid_data(&message));
+                }
+                edges.push((a as usize, b as usize));
+            }
+            self.edges += edges.len() as u64;
+            merges += self.graph.connect_all(edges.drain(..));
+            if complete < len {
+                return Err(invalid_data(&format!("edge {} is truncated", self.edges)));
+            }
+            if len < buffer.len() {
+                return Ok(merges);
+            }
+        }
+    }
+
+    /// Writes the label of every node (see `Graph::labels`) to the given
+    /// writer, so that the labels of independent jobs can be combined by
+    /// `merge_labels`.
+    /// 
+    /// The labels are preceded by the number of nodes, all as little-endian
+    /// integers of 8 bytes.
+    /// 
+    /// # Arguments
+    /// * `writer` The writer to write the labels to.
+    pub fn write_labels<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&(self.graph.count() as u64).to_le_bytes())?;
+        let mut buffer = Vec::with_capacity(CHUNK * 8);
+        for label in self.graph.labels() {
+            buffer.extend_from_slice(&(label as u64).to_le_bytes());
+            if buffer.len() == buffer.capacity() {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+        writer.write_all(&buffer)?;
+        writer.flush()
+    }
+}
+
+/// Returns the components connecting the nodes with the same label in any of
+/// the label arrays read from the given readers (written by `write_labels`),
+/// which is the connectivity of the union of the edges of every job.
+/// 
+/// Returns an error of kind `InvalidData` if the label arrays do not all
+/// refer to the given number of nodes.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `readers` The readers to read the label arrays from.
+/// 
+/// # Example
+/// ```
+/// use wuf::external::{self, ExternalComponents, Width};
+/// 
+/// let mut spilled = vec![Vec::new(), Vec::new()];
+/// for (edge, labels) in [[0u32, 1], [2, 3]].iter().zip(spilled.iter_mut()) {
+///     let bytes: Vec<u8> = edge.iter().flat_map(|id| id.to_le_bytes()).collect();
+///     let mut components = ExternalComponents::new(4);
+///     components.connect_reader(bytes.as_slice(), Width::U32).unwrap();
+///     components.write_labels(labels).unwrap();
+/// }
+/// let graph = external::merge_labels(4, spilled.iter().map(Vec::as_slice)).unwrap();
+/// assert_eq!(2, graph.component_count());
+/// ```
+pub fn merge_labels<R, L>(n: usize, readers: L) -> io::Result<Graph>
+    where R: Read, L: IntoIterator<Item = R>
+{
+    let mut graph = Graph::new(n);
+    let mut buffer = vec![0; CHUNK * 8];
+    for mut reader in readers {
+        let mut count = [0; 8];
+        reader.read_exact(&mut count)?;
+        if u64::from_le_bytes(count) != n as u64 {
+            return Err(invalid_data("labels of a different number of nodes"));
+        }
+        // first node with each label, hence in the same component
+        let mut firsts: Vec<usize> = Vec::new();
+        let mut id = 0;
+        while id < n {
+            let len = (n - id).min(CHUNK) * 8;
+            reader.read_exact(&mut buffer[..len])?;
+            for bytes in buffer[..len].chunks(8) {
+                let label = decode(bytes);
+                if label > firsts.len() as u64 || label >= n as u64 {
+                    return Err(invalid_data("labels are not assigned by first appearance"));
+                }
+                if label == firsts.len() as u64 {
+                    firsts.push(id);
+                } else {
+                    graph.connect(firsts[label as usize], id);
+                }
+                id += 1;
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Reads from the given reader until the buffer is full or the reader is
+/// exhausted, returning the number of bytes read.
+fn fill<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err)
+        }
+    }
+    Ok(len)
+}
+
+/// Decodes a little-endian integer of at most 8 bytes.
+fn decode(bytes: &[u8]) -> u64 {
+    let mut value = [0; 8];
+    value[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(value)
+}
+
+/// Returns a new error of kind `InvalidData` with the given message.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes the given edges as a binary edge list of the given width.
+    fn encode(edges: &[(usize, usize)], width: Width) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for &(a, b) in edges {
+            bytes.extend_from_slice(&(a as u64).to_le_bytes()[..width.bytes()]);
+            bytes.extend_from_slice(&(b as u64).to_le_bytes()[..width.bytes()]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn should_connect_in_chunks() {
+        let edges: Vec<(usize, usize)> = (0..99).map(|id| (id, (id * 7 + 3) % 100)).collect();
+        let mut components = ExternalComponents::with_chunk(100, 8);
+        let merges = components.connect_reader(encode(&edges, Width::U64).as_slice(), Width::U64).unwrap();
+        let mut expected = Graph::new(100);
+        assert_eq!(expected.connect_all(edges.iter().cloned()), merges);
+        assert_eq!(99, components.edge_count());
+        assert!(components.graph().same_partition(&expected));
+        // the edges before an invalid one are still connected
+        let mut components = ExternalComponents::with_chunk(4, 2);
+        let bytes = encode(&[(0, 1), (1, 2), (2, 9), (3, 0)], Width::U32);
+        let err = components.connect_reader(bytes.as_slice(), Width::U32).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(2, components.edge_count());
+        assert_eq!(2, components.graph().component_count());
+        let err = components.connect_reader(&bytes[..3], Width::U32).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn should_merge_labels() {
+        let jobs = [vec![(0, 1), (2, 3)], vec![(1, 2), (4, 5)], vec![]];
+        let mut spilled = Vec::new();
+        for edges in &jobs {
+            let mut components = ExternalComponents::new(7);
+            components.connect_reader(encode(edges, Width::U32).as_slice(), Width::U32).unwrap();
+            let mut labels = Vec::new();
+            components.write_labels(&mut labels).unwrap();
+            assert_eq!(8 * 8, labels.len());
+            spilled.push(labels);
+        }
+        let graph = merge_labels(7, spilled.iter().map(Vec::as_slice)).unwrap();
+        assert_eq!(vec![0, 0, 0, 0, 1, 1, 2], graph.labels());
+        let err = merge_labels(8, spilled.iter().map(Vec::as_slice)).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+}
@@ -0,0 +1,161 @@
+//! C bindings (with the `ffi` feature), declared by the header
+//! `include/wuf.h`.
+//! 
+//! A graph is an opaque pointer returned by `wuf_graph_new` and released by
+//! `wuf_graph_free`, while operations on nodes out of bounds return an error
+//! value rather than unwinding into the caller. A static library can be built
+//! with `cargo rustc --release --features ffi --crate-type staticlib`.
+
+use std::{ptr, slice};
+
+use graph::Graph;
+
+/// Value returned by the operations on nodes out of bounds.
+const OUT_OF_BOUNDS: i32 = -1;
+
+/// Returns a new graph with the given number of nodes, to be released with
+/// `wuf_graph_free`, or null if the number of nodes exceeds `MAX_NODES` or
+/// their array cannot be allocated, rather than unwinding into the caller or
+/// aborting.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+#[no_mangle]
+pub extern "C" fn wuf_graph_new(n: usize) -> *mut Graph {
+    match Graph::try_with_nodes(n) {
+        Some(graph) => Box::into_raw(Box::new(graph)),
+        None => ptr::null_mut()
+    }
+}
+
+/// Releases the given graph, unless null.
+/// 
+/// # Safety
+/// The graph must have been returned by `wuf_graph_new` and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_free(graph: *mut Graph) {
+    if !graph.is_null() {
+        drop(Box::from_raw(graph));
+    }
+}
+
+/// Returns the number of nodes of the given graph.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_count(graph: *const Graph) -> usize {
+    (*graph).count()
+}
+
+/// Returns the number of disjoint components of the given graph.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_component_count(graph: *const Graph) -> usize {
+    (*graph).component_count()
+}
+
+/// Connects the two given nodes, and returns 1 if they belonged to different
+/// components, 0 if they were already connected, or -1 if a node is out of
+/// bounds.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_union(graph: *mut Graph, a: usize, b: usize) -> i32 {
+    match (*graph).try_connect(a, b) {
+        Ok(root) => root.is_some() as i32,
+        Err(_) => OUT_OF_BOUNDS
+    }
+}
+
+/// Connects the `len` edges stored as consecutive pairs of node IDs in
+/// `edges`, and returns the number of merges, or -1 if a node is out of
+/// bounds, after connecting the edges before it.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`, and `edges`
+/// must be valid for reads of `2 * len` node IDs (or null if `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_union_all(graph: *mut Graph, edges: *const usize, len: usize) -> isize {
+    if len == 0 {
+        return 0;
+    }
+    let edges = slice::from_raw_parts(edges, 2 * len);
+    let mut merges = 0;
+    for pair in edges.chunks(2) {
+        match (*graph).try_connect(pair[0], pair[1]) {
+            Ok(Some(_)) => merges += 1,
+            Ok(None) => {}
+            Err(_) => return OUT_OF_BOUNDS as isize
+        }
+    }
+    merges
+}
+
+/// Returns 1 if the two given nodes are connected, 0 if they are not, or -1 if
+/// a node is out of bounds.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_connected(graph: *mut Graph, a: usize, b: usize) -> i32 {
+    match (*graph).try_connected(a, b) {
+        Ok(connected) => connected as i32,
+        Err(_) => OUT_OF_BOUNDS
+    }
+}
+
+/// Stores the root of the given node in `root` (unless null), and returns 0,
+/// or returns -1 if the node is out of bounds.
+/// 
+/// # Safety
+/// The graph must be a valid pointer returned by `wuf_graph_new`, and `root`
+/// must be either null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn wuf_graph_find(graph: *mut Graph, id: usize, root: *mut usize) -> i32 {
+    match (*graph).try_find(id) {
+        Ok(found) => {
+            if !root.is_null() {
+                ptr::write(root, found);
+            }
+            0
+        }
+        Err(_) => OUT_OF_BOUNDS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_call_through_pointers() {
+        let graph = wuf_graph_new(4);
+        unsafe {
+            assert_eq!(4, wuf_graph_count(graph));
+            assert_eq!(1, wuf_graph_union(graph, 0, 1));
+            assert_eq!(0, wuf_graph_union(graph, 1, 0));
+            assert_eq!(OUT_OF_BOUNDS, wuf_graph_union(graph, 0, 4));
+            assert_eq!(1, wuf_graph_connected(graph, 1, 0));
+            assert_eq!(0, wuf_graph_connected(graph, 1, 2));
+            assert_eq!(OUT_OF_BOUNDS, wuf_graph_connected(graph, 9, 2));
+            let mut root = usize::MAX;
+            assert_eq!(0, wuf_graph_find(graph, 1, &mut root));
+            assert_eq!(0, root);
+            assert_eq!(OUT_OF_BOUNDS, wuf_graph_find(graph, 4, ptr::null_mut()));
+            assert_eq!(3, wuf_graph_component_count(graph));
+            assert_eq!(0, wuf_graph_union_all(graph, ptr::null(), 0));
+            assert_eq!(1, wuf_graph_union_all(graph, [2, 3, 3, 2].as_ptr(), 2));
+            assert_eq!(-1, wuf_graph_union_all(graph, [0, 2, 0, 4].as_ptr(), 2));
+            assert_eq!(1, wuf_graph_component_count(graph));
+            wuf_graph_free(graph);
+            wuf_graph_free(ptr::null_mut());
+        }
+        assert!(wuf_graph_new(Graph::<usize>::MAX_NODES + 1).is_null());
+        assert!(wuf_graph_new(usize::MAX / 4).is_null());
+        assert!(wuf_graph_new(1 << (usize::BITS - 5)).is_null());
+    }
+}
@@ -0,0 +1,298 @@
+#[cfg(feature = "rkyv")]
+use std::error::Error;
+#[cfg(feature = "rkyv")]
+use std::fmt;
+use std::vec::Vec;
+
+#[cfg(feature = "rkyv")]
+use rkyv::bytecheck::Verify;
+#[cfg(feature = "rkyv")]
+use rkyv::rancor::{fail, Fallible, Source};
+
+#[cfg(feature = "rkyv")]
+use error::WufError;
+use graph::Graph;
+#[cfg(feature = "rkyv")]
+use index::ArchiveIndex;
+use index::Index;
+
+/// Read-only partition where every node points directly to its root, obtained
+/// by freezing a `Graph`.
+/// 
+/// Queries are constant time and only need a shared reference, so that a
+/// frozen partition can be shared and queried from many threads at once.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::Graph::new(10);
+/// graph.connect(0, 1);
+/// let frozen = graph.freeze();
+/// std::thread::scope(|s| {
+///     s.spawn(|| assert!(frozen.connected(0, 1)));
+///     s.spawn(|| assert!(!frozen.connected(0, 2)));
+/// });
+/// ```
+/// 
+/// With the `rkyv` feature, a frozen partition with a fixed-width index type
+/// (see `ArchiveIndex`) can be archived, and its archived form
+/// (`ArchivedFrozenPartition`) queried directly from the bytes, without
+/// deserializing. This excludes the default index type `usize`, which rkyv
+/// archives with 32 bits: freeze a `Graph<u32>` or a `Graph<u64>` instead (see
+/// `Graph::with_nodes`). The entries are validated when the bytes are
+/// accessed with `rkyv::access`, so that the archived queries never index out
+/// of bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize),
+    rkyv(serialize_bounds(I: ArchiveIndex), bytecheck(verify))
+)]
+pub struct FrozenPartition<I: Index = usize> {
+    nodes: Vec<I>,      // root of each node, or encoded size of each root
+    components: usize   // number of disjoint components
+}
+
+impl<I: Index> FrozenPartition<I> {
+
+    /// Returns a new FrozenPartition from the given entries, where every node
+    /// stores its root and every root stores its encoded size.
+    pub(crate) fn from_parts(nodes: Vec<I>, components: usize) -> FrozenPartition<I> {
+        FrozenPartition { nodes, components }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let frozen = wuf::Graph::new(10).freeze();
+    /// assert_eq!(10, frozen.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.freeze().component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the root of the given node, in constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// let frozen = graph.freeze();
+    /// assert_eq!(frozen.find(0), frozen.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        let entry = self.nodes[id];
+        if Graph::<I>::is_root_entry(entry) {
+            id
+        } else {
+            entry.index()
+        }
+    }
+
+    /// Returns true only if the two given nodes are connected, in constant
+    /// time.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to,
+    /// in constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(2, graph.freeze().component_size(1));
+    /// ```
+    pub fn component_size(&self, id: usize) -> usize {
+        I::MAX - self.nodes[self.find(id)].index()
+    }
+}
+
+/// Error of an archived partition whose number of components does not match
+/// its roots.
+#[cfg(feature = "rkyv")]
+#[derive(Debug)]
+struct ComponentMismatch {
+    expected: usize,    // number of roots
+    found: usize        // number of components archived
+}
+
+#[cfg(feature = "rkyv")]
+impl fmt::Display for ComponentMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} roots do not match {} components", self.expected, self.found)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl Error for ComponentMismatch {}
+
+// checks that every node points to a root within bounds, and that the sizes and
+// the number of components match the roots, when the bytes are accessed
+#[cfg(feature = "rkyv")]
+unsafe impl<I, C> Verify<C> for ArchivedFrozenPartition<I>
+    where I: ArchiveIndex, C: Fallible + ?Sized, C::Error: Source
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        let n = self.nodes.len();
+        let mut sizes = vec![0; n];
+        for (id, entry) in self.nodes.iter().enumerate() {
+            let entry = I::archived_index(entry);
+            let root = if entry > Graph::<I>::MAX_NODES { id } else { entry };
+            if root >= n {
+                fail!(WufError::InvalidParent { id, parent: root });
+            }
+            sizes[root] += 1;
+        }
+        let mut roots = 0;
+        for (id, entry) in self.nodes.iter().enumerate() {
+            let entry = I::archived_index(entry);
+            // non-roots are the root of no other node
+            let size = if entry > Graph::<I>::MAX_NODES { I::MAX - entry } else { 0 };
+            if size != sizes[id] {
+                fail!(WufError::SizeMismatch { id, expected: sizes[id], found: size });
+            }
+            roots += (size > 0) as usize;
+        }
+        let components = self.components.to_native() as usize;
+        if roots != components {
+            fail!(ComponentMismatch { expected: roots, found: components });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<I: ArchiveIndex> ArchivedFrozenPartition<I> {
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components.
+    pub fn component_count(&self) -> usize {
+        self.components.to_native() as usize
+    }
+
+    /// Returns the root of the given node, in constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::<u32>::with_nodes(10);
+    /// graph.connect(0, 1);
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&graph.freeze()).unwrap();
+    /// let archived = rkyv::access::<wuf::ArchivedFrozenPartition<u32>, rkyv::rancor::Error>(&bytes)
+    ///     .unwrap();
+    /// assert_eq!(archived.find(0), archived.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        let entry = I::archived_index(&self.nodes[id]);
+        if entry > Graph::<I>::MAX_NODES {
+            id
+        } else {
+            entry
+        }
+    }
+
+    /// Returns true only if the two given nodes are connected, in constant
+    /// time.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to,
+    /// in constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn component_size(&self, id: usize) -> usize {
+        I::MAX - I::archived_index(&self.nodes[self.find(id)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_freeze() {
+        let mut graph = Graph::<u32>::with_nodes(6);
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.connect(1, 3);
+        graph.connect(4, 5);
+        let root = graph.find(2);
+        let frozen = graph.freeze();
+        assert_eq!(6, frozen.count());
+        assert_eq!(2, frozen.component_count());
+        assert!((0..4).all(|id| frozen.find(id) == root));
+        assert!((0..4).all(|id| frozen.component_size(id) == 4));
+        assert!(frozen.connected(4, 5));
+        assert!(!frozen.connected(3, 4));
+        assert_eq!(2, frozen.component_size(4));
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn should_access_archive() {
+        use rkyv::rancor::Error;
+
+        let mut graph = Graph::<u16>::with_nodes(6);
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.connect(1, 3);
+        let frozen = graph.freeze();
+        let bytes = rkyv::to_bytes::<Error>(&frozen).unwrap();
+        let archived = rkyv::access::<ArchivedFrozenPartition<u16>, Error>(&bytes).unwrap();
+        assert_eq!(6, archived.count());
+        assert_eq!(3, archived.component_count());
+        for id in 0..6 {
+            assert_eq!(frozen.find(id), archived.find(id));
+            assert_eq!(frozen.component_size(id), archived.component_size(id));
+        }
+        assert!(archived.connected(0, 2));
+        assert!(!archived.connected(0, 4));
+        let deserialized = rkyv::deserialize::<FrozenPartition<u16>, Error>(archived).unwrap();
+        assert_eq!(frozen, deserialized);
+        // entries out of bounds, pointing to non-roots, or with wrong sizes
+        for nodes in [vec![7, u16::MAX - 1], vec![1, 0], vec![u16::MAX - 2, 0, 1]].iter() {
+            let corrupt = FrozenPartition::from_parts(nodes.clone(), 1);
+            let bytes = rkyv::to_bytes::<Error>(&corrupt).unwrap();
+            assert!(rkyv::access::<ArchivedFrozenPartition<u16>, Error>(&bytes).is_err());
+        }
+        let corrupt = FrozenPartition::from_parts(vec![u16::MAX - 1, u16::MAX - 1], 1);
+        let bytes = rkyv::to_bytes::<Error>(&corrupt).unwrap();
+        assert!(rkyv::access::<ArchivedFrozenPartition<u16>, Error>(&bytes).is_err());
+    }
+}
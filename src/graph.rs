@@ -0,0 +1,2649 @@
+#[cfg(feature = "allocator_api")]
+use std::alloc::{Allocator, Global};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::ops::Range;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use error::WufError;
+use frozen::FrozenPartition;
+use index::Index;
+use node::{self, NodeId};
+use union_find::UnionFind;
+
+/// Strategy used to choose which root survives when two components are
+/// connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Linking {
+    /// Link the root of the smaller tree below the root of the larger tree.
+    #[default]
+    Size,
+    /// Link the root of lower rank below the root of higher rank, where the
+    /// rank is an upper bound on the height of the tree. Ranks are bounded by
+    /// `log2(n)` and are stored as single bytes.
+    Rank,
+}
+
+/// Strategy used to shorten the paths traversed while looking for a root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Compression {
+    /// Make every node in the path point directly to the root (two passes).
+    Full,
+    /// Make every other node in the path point to its grandparent.
+    #[default]
+    Halving,
+    /// Make every node in the path point to its grandparent.
+    Splitting,
+    /// Leave the paths untouched, so that the forest only changes on unions.
+    None,
+}
+
+// defines Graph with the given generic parameters, and the allocator of its
+// arrays of nodes if any
+macro_rules! define_graph {
+    ([$($params:tt)*] $($alloc:ident)?) => {
+        /// Weighted union-find over a fixed range of nodes, whose IDs are
+        /// stored with the index type `I` (see `Index`).
+        /// 
+        /// Parents and sizes share a single array: every node stores the ID of
+        /// its parent, while roots store the size of their tree encoded as
+        /// `I::MAX - size`, which never collides with a node ID since the
+        /// number of nodes is bounded by `MAX_NODES`.
+        /// 
+        /// The members of each component can also be tracked in circular lists
+        /// (see `GraphBuilder::track_members`), at the cost of one more index
+        /// per node, so that `members` takes time linear in the size of the
+        /// component rather than in the number of nodes.
+        /// 
+        /// With the `allocator_api` feature (nightly only), the arrays of the
+        /// nodes live in the allocator `A` (see `with_nodes_in`).
+        /// 
+        /// The `Debug` output shows the components rather than the internal
+        /// forest, each component in ascending order of ID, ordered by smallest
+        /// ID.
+        /// 
+        /// # Example
+        /// ```
+        /// let mut graph = wuf::Graph::new(5);
+        /// graph.connect(3, 0);
+        /// graph.connect(1, 2);
+        /// assert_eq!("{{0, 3}, {1, 2}, {4}}", format!("{:?}", graph));
+        /// ```
+        #[derive(Clone)]
+        pub struct Graph<$($params)*> {
+            nodes: Vec<I $(, $alloc)?>, // parent of each node, or encoded size of each root
+            ranks: Vec<u8 $(, $alloc)?>, // rank of each node, only used when linking by rank
+            next: Vec<I $(, $alloc)?>, // next node in the circular list of each component, if tracked
+            tracking: bool,     // whether the members of each component are tracked
+            linking: Linking,   // strategy used to link roots
+            compression: Compression, // strategy used to compress paths
+            tag: usize,         // tag identifying the nodes of this graph
+            components: usize,  // number of disjoint components
+            pairs: usize,       // number of pairs of connected nodes
+            free: Vec<I>,       // IDs of the retired nodes, reused by add_node
+            retired: Vec<u64>,  // bitset of the retired nodes
+            generations: Vec<u32> // number of times each node was reused, if ever
+        }
+    };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+define_graph!([I: Index = usize]);
+#[cfg(feature = "allocator_api")]
+define_graph!([I: Index = usize, A: Allocator = Global] A);
+
+// implements the given methods (or trait) for graphs in any allocator with the
+// `allocator_api` feature
+macro_rules! impl_graph {
+    ($(#[$attr:meta])* impl $trait:path { $($body:tt)* }) => {
+        $(#[$attr])*
+        #[cfg(not(feature = "allocator_api"))]
+        impl<I: Index> $trait for Graph<I> { $($body)* }
+
+        $(#[$attr])*
+        #[cfg(feature = "allocator_api")]
+        impl<I: Index, A: Allocator> $trait for Graph<I, A> { $($body)* }
+    };
+    ($($body:tt)*) => {
+        #[cfg(not(feature = "allocator_api"))]
+        impl<I: Index> Graph<I> { $($body)* }
+
+        #[cfg(feature = "allocator_api")]
+        impl<I: Index, A: Allocator> Graph<I, A> { $($body)* }
+    };
+}
+
+impl Graph {
+
+    /// Returns a new Graph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    pub fn new(n: usize) -> Graph {
+        Graph::with_nodes(n)
+    }
+
+    /// Returns a new Graph with the given number of nodes, able to hold at
+    /// least `capacity` nodes without reallocating.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `capacity` Number of nodes the graph can hold without reallocating.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::with_capacity(10, 100);
+    /// assert_eq!(10, graph.count());
+    /// assert!(graph.capacity() >= 100);
+    /// ```
+    pub fn with_capacity(n: usize, capacity: usize) -> Graph {
+        Graph::with_nodes_and_capacity(n, capacity)
+    }
+
+    /// Returns a new Graph with the given number of nodes, which links
+    /// components according to the given strategy.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `linking` Strategy used to link components.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Graph, Linking};
+    /// 
+    /// let graph = Graph::with_linking(10, Linking::Rank);
+    /// assert_eq!(Linking::Rank, graph.linking());
+    /// ```
+    pub fn with_linking(n: usize, linking: Linking) -> Graph {
+        Graph::with_config(n, n, linking, Compression::default())
+    }
+
+    /// Returns a new Graph with the given number of nodes, which compresses
+    /// paths according to the given strategy.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `compression` Strategy used to compress paths.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Compression, Graph};
+    /// 
+    /// let graph = Graph::with_compression(10, Compression::Full);
+    /// assert_eq!(Compression::Full, graph.compression());
+    /// ```
+    pub fn with_compression(n: usize, compression: Compression) -> Graph {
+        Graph::with_config(n, n, Linking::default(), compression)
+    }
+
+    /// Returns a new Graph from the given parent of each node, where roots
+    /// are their own parent, after checking that every parent belongs to the
+    /// graph and that there are no cycles. The sizes of the trees are
+    /// recomputed from the parents.
+    /// 
+    /// # Arguments
+    /// * `parents` The parent of each node.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Graph, WufError};
+    /// 
+    /// let mut graph = Graph::from_parent_vec(vec![0, 0, 1, 3]).unwrap();
+    /// assert_eq!(2, graph.component_count());
+    /// assert_eq!(3, graph.component_size(2));
+    /// 
+    /// let err = Graph::from_parent_vec(vec![1, 2, 0]).unwrap_err();
+    /// assert_eq!(WufError::Cycle { id: 0 }, err);
+    /// ```
+    pub fn from_parent_vec(parents: Vec<usize>) -> Result<Graph, WufError> {
+        Graph::from_checked_parts(
+            parents,
+            None,
+            Vec::new(),
+            Linking::default(),
+            Compression::default()
+        )
+    }
+
+    /// Returns a new Graph with the given number of nodes, partitioned
+    /// according to the given components, such as the ones returned by
+    /// `into_sets`. The nodes not listed in any component are isolated.
+    /// 
+    /// Returns an error if a node is out of bounds or if it belongs to more
+    /// than one component.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `components` The disjoint sets of nodes forming each component.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Graph, WufError};
+    /// 
+    /// let graph = Graph::from_components(5, &[vec![0, 3], vec![1, 2]]).unwrap();
+    /// assert_eq!(vec![vec![0, 3], vec![1, 2], vec![4]], graph.into_sets());
+    /// 
+    /// let err = Graph::from_components(5, &[vec![0, 3], vec![3, 4]]).unwrap_err();
+    /// assert_eq!(WufError::Overlap { id: 3 }, err);
+    /// ```
+    pub fn from_components(n: usize, components: &[Vec<usize>]) -> Result<Graph, WufError> {
+        let mut graph = Graph::new(n);
+        let mut listed = vec![false; n];
+        for component in components {
+            for &id in component {
+                if id >= n {
+                    return Err(WufError::OutOfBounds { id, count: n });
+                }
+                if listed[id] {
+                    return Err(WufError::Overlap { id });
+                }
+                listed[id] = true;
+                graph.connect(component[0], id);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Returns a new Graph with the given number of nodes, connected by the
+    /// given edges.
+    /// 
+    /// Panics if a node does not belong to the graph.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::from_edges(5, vec![(0, 1), (3, 4)]);
+    /// assert_eq!(vec![vec![0, 1], vec![2], vec![3, 4]], graph.into_sets());
+    /// ```
+    pub fn from_edges<E: IntoIterator<Item = (usize, usize)>>(n: usize, edges: E) -> Graph {
+        let mut graph = Graph::new(n);
+        graph.connect_all(edges);
+        graph
+    }
+}
+
+impl<I: Index> Graph<I> {
+
+    /// Returns a new Graph with the given number of nodes, storing node IDs
+    /// with the index type `I`.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph, at most `MAX_NODES`.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::<u32>::with_nodes(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn with_nodes(n: usize) -> Graph<I> {
+        Graph::with_nodes_and_capacity(n, n)
+    }
+
+    /// Returns a new Graph with the given number of nodes, storing node IDs
+    /// with the index type `I`, able to hold at least `capacity` nodes without
+    /// reallocating.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph, at most `MAX_NODES`.
+    /// * `capacity` Number of nodes the graph can hold without reallocating.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::<u16>::with_nodes_and_capacity(10, 100);
+    /// assert_eq!(10, graph.count());
+    /// assert!(graph.capacity() >= 100);
+    /// ```
+    pub fn with_nodes_and_capacity(n: usize, capacity: usize) -> Graph<I> {
+        Graph::with_config(n, capacity, Linking::default(), Compression::default())
+    }
+
+    /// Same as `with_nodes`, but returns `None` rather than panicking if the
+    /// number of nodes exceeds `MAX_NODES`, or aborting if their array cannot
+    /// be allocated.
+    #[cfg(feature = "ffi")]
+    pub(crate) fn try_with_nodes(n: usize) -> Option<Graph<I>> {
+        if n > Self::MAX_NODES {
+            return None;
+        }
+        let mut graph = Graph::with_config(0, 0, Linking::default(), Compression::default());
+        graph.nodes.try_reserve_exact(n).ok()?;
+        graph.add_nodes(n);
+        Some(graph)
+    }
+
+    /// Returns a new Graph with the given number of nodes, capacity, linking
+    /// and compression strategies.
+    pub(crate) fn with_config(
+        n: usize,
+        capacity: usize,
+        linking: Linking,
+        compression: Compression
+    ) -> Graph<I> {
+        let capacity = capacity.max(n);
+        let mut graph = Graph {
+            nodes: Vec::with_capacity(capacity),
+            ranks: Vec::new(),
+            next: Vec::new(),
+            tracking: false,
+            linking,
+            compression,
+            tag: node::next_tag(),
+            components: 0,
+            pairs: 0,
+            free: Vec::new(),
+            retired: Vec::new(),
+            generations: Vec::new()
+        };
+        if linking == Linking::Rank {
+            graph.ranks.reserve(capacity);
+        }
+        graph.add_nodes(n);
+        graph
+    }
+
+    /// Decomposes the graph into its underlying arrays, without copying: the
+    /// entry of each node, and the rank of each node (empty unless linking by
+    /// rank).
+    /// 
+    /// The entry of a node is the ID of its parent, unless the node is a root,
+    /// in which case the entry is `I::MAX - size`, where `size` is the number
+    /// of nodes in its tree.
+    /// 
+    /// Panics if any node is retired (see `remove_node`), since the arrays do
+    /// not record which ones.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::<u32>::with_nodes(3);
+    /// graph.connect(0, 1);
+    /// let (nodes, ranks) = graph.into_raw_parts();
+    /// assert_eq!(vec![u32::MAX - 2, 0, u32::MAX - 1], nodes);
+    /// assert!(ranks.is_empty());
+    /// ```
+    pub fn into_raw_parts(self) -> (Vec<I>, Vec<u8>) {
+        if let Err(err) = self.check_not_retired() {
+            panic!("{}", err);
+        }
+        (self.nodes, self.ranks)
+    }
+
+    /// Returns a new Graph from the given underlying arrays, without copying
+    /// nor checking them (see `into_raw_parts`).
+    /// 
+    /// # Safety
+    /// The entries must describe a valid forest, as returned by
+    /// `into_raw_parts`: every parent must be lower than the number of nodes,
+    /// there must be no cycles, and every root must store the size of its
+    /// tree. There must be one rank per node if linking by rank, and none
+    /// otherwise.
+    /// 
+    /// # Arguments
+    /// * `nodes` The entry of each node.
+    /// * `ranks` The rank of each node, only when linking by rank.
+    /// * `linking` Strategy used to link components.
+    /// * `compression` Strategy used to compress paths.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Compression, Graph, Linking};
+    /// 
+    /// let mut graph = Graph::new(3);
+    /// graph.connect(0, 1);
+    /// let (nodes, ranks) = graph.into_raw_parts();
+    /// let mut graph = unsafe {
+    ///     Graph::from_raw_parts(nodes, ranks, Linking::Size, Compression::Halving)
+    /// };
+    /// assert!(graph.connected(0, 1));
+    /// ```
+    pub unsafe fn from_raw_parts(
+        nodes: Vec<I>,
+        ranks: Vec<u8>,
+        linking: Linking,
+        compression: Compression
+    ) -> Graph<I> {
+        let roots = nodes.iter().filter(|&&entry| Self::is_root_entry(entry));
+        let components = roots.clone().count();
+        let pairs = roots.map(|&entry| pairs_within(I::MAX - entry.index())).sum();
+        Graph {
+            nodes,
+            ranks,
+            next: Vec::new(),
+            tracking: false,
+            linking,
+            compression,
+            tag: node::next_tag(),
+            components,
+            pairs,
+            free: Vec::new(),
+            retired: Vec::new(),
+            generations: Vec::new()
+        }
+    }
+
+    /// Same as `from_raw_parts`, but checks that the underlying arrays
+    /// describe a valid forest, returning an error otherwise.
+    /// 
+    /// # Arguments
+    /// * `nodes` The entry of each node.
+    /// * `ranks` The rank of each node, only when linking by rank.
+    /// * `linking` Strategy used to link components.
+    /// * `compression` Strategy used to compress paths.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{Compression, Graph, Linking, WufError};
+    /// 
+    /// let nodes = vec![u32::MAX - 2, 0, 3];
+    /// let result = Graph::try_from_raw_parts(nodes, vec![], Linking::Size, Compression::Halving);
+    /// assert_eq!(WufError::InvalidParent { id: 2, parent: 3 }, result.unwrap_err());
+    /// ```
+    pub fn try_from_raw_parts(
+        nodes: Vec<I>,
+        ranks: Vec<u8>,
+        linking: Linking,
+        compression: Compression
+    ) -> Result<Graph<I>, WufError> {
+        let n = nodes.len();
+        let mut parents = Vec::with_capacity(n);
+        let mut sizes = Vec::with_capacity(n);
+        for (id, entry) in nodes.into_iter().enumerate() {
+            if Self::is_root_entry(entry) {
+                parents.push(id);
+                sizes.push(I::MAX - entry.index());
+            } else {
+                parents.push(entry.index());
+                sizes.push(0);
+            }
+        }
+        Graph::from_checked_parts(parents, Some(&sizes), ranks, linking, compression)
+    }
+
+    /// Converts the graph into a read-only partition where every node points
+    /// directly to its root, supporting constant time queries on shared
+    /// references.
+    /// 
+    /// Retired nodes (see `remove_node`) become components of their own.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// let frozen = graph.freeze();
+    /// assert!(frozen.connected(0, 1));
+    /// ```
+    pub fn freeze(mut self) -> FrozenPartition<I> {
+        self.flatten();
+        // retired nodes are frozen as components of their own
+        FrozenPartition::from_parts(self.nodes, self.components + self.free.len())
+    }
+
+    /// Returns a new Graph from the given parent of each node, where roots are
+    /// their own parent, after checking that every parent belongs to the graph
+    /// and that there are no cycles.
+    /// 
+    /// If given, the size of the tree rooted at each node (0 for non-roots) is
+    /// checked against the actual structure. Ranks must not be empty only when
+    /// linking by rank, in which case the rank of every root must be at most
+    /// the binary logarithm of the size of its tree, and the rank of every
+    /// other node must be lower than the rank of its parent, as when linking
+    /// by rank with any compression, so that ranks can never overflow.
+    pub(crate) fn from_checked_parts(
+        parents: Vec<usize>,
+        sizes: Option<&[usize]>,
+        ranks: Vec<u8>,
+        linking: Linking,
+        compression: Compression
+    ) -> Result<Graph<I>, WufError> {
+        let n = parents.len();
+        if n > Self::MAX_NODES {
+            return Err(WufError::TooManyNodes { count: n, max: Self::MAX_NODES });
+        }
+        if let Some((id, &parent)) = parents.iter().enumerate().find(|&(_, &p)| p >= n) {
+            return Err(WufError::InvalidParent { id, parent });
+        }
+        let expected_ranks = if linking == Linking::Rank { n } else { 0 };
+        if ranks.len() != expected_ranks {
+            return Err(WufError::LengthMismatch { len: ranks.len(), count: n });
+        }
+        // resolve the root of every node, visiting each node once: roots are
+        // resolved first, then every path is walked until a resolved node
+        const UNRESOLVED: usize = usize::MAX;
+        let mut roots = vec![UNRESOLVED; n];
+        let mut visited = vec![false; n];
+        let mut path = Vec::new();
+        for id in 0..n {
+            let mut node = id;
+            while roots[node] == UNRESOLVED && parents[node] != node {
+                if visited[node] {
+                    return Err(WufError::Cycle { id: node });
+                }
+                visited[node] = true;
+                path.push(node);
+                node = parents[node];
+            }
+            let root = if parents[node] == node { node } else { roots[node] };
+            roots[node] = root;
+            for node in path.drain(..) {
+                roots[node] = root;
+            }
+        }
+        let mut actual_sizes = vec![0; n];
+        for &root in &roots {
+            actual_sizes[root] += 1;
+        }
+        if let Some(sizes) = sizes {
+            if sizes.len() != n {
+                return Err(WufError::LengthMismatch { len: sizes.len(), count: n });
+            }
+            let mismatch = (0..n).find(|&id| sizes[id] != actual_sizes[id]);
+            if let Some(id) = mismatch {
+                return Err(WufError::SizeMismatch {
+                    id,
+                    expected: actual_sizes[id],
+                    found: sizes[id]
+                });
+            }
+        }
+        if linking == Linking::Rank {
+            let invalid = (0..n).find(|&id| match parents[id] {
+                parent if parent == id => {
+                    let log2 = usize::BITS - 1 - actual_sizes[id].leading_zeros();
+                    u32::from(ranks[id]) > log2
+                }
+                parent => ranks[id] >= ranks[parent]
+            });
+            if let Some(id) = invalid {
+                return Err(WufError::InvalidRank { id, rank: ranks[id] });
+            }
+        }
+        let mut components = 0;
+        let mut pairs = 0;
+        let nodes = parents.into_iter().enumerate().map(|(id, parent)| {
+            if parent == id {
+                components += 1;
+                pairs += pairs_within(actual_sizes[id]);
+                Self::root_entry(actual_sizes[id])
+            } else {
+                I::from_usize(parent)
+            }
+        }).collect();
+        Ok(Graph {
+            nodes,
+            ranks,
+            next: Vec::new(),
+            tracking: false,
+            linking,
+            compression,
+            tag: node::next_tag(),
+            components,
+            pairs,
+            free: Vec::new(),
+            retired: Vec::new(),
+            generations: Vec::new()
+        })
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<I: Index, A: Allocator + Clone> Graph<I, A> {
+
+    /// Returns a new Graph with the given number of nodes, storing node IDs
+    /// with the index type `I`, whose arrays of nodes live in the given
+    /// allocator (such as an arena, or an allocator backed by huge pages).
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph, at most `MAX_NODES`.
+    /// * `alloc` The allocator of the arrays of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// 
+    /// let mut graph = wuf::Graph::<u32, _>::with_nodes_in(10, Global);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn with_nodes_in(n: usize, alloc: A) -> Graph<I, A> {
+        let mut graph = Graph {
+            nodes: Vec::with_capacity_in(n, alloc.clone()),
+            ranks: Vec::new_in(alloc.clone()),
+            next: Vec::new_in(alloc),
+            tracking: false,
+            linking: Linking::default(),
+            compression: Compression::default(),
+            tag: node::next_tag(),
+            components: 0,
+            pairs: 0,
+            free: Vec::new(),
+            retired: Vec::new(),
+            generations: Vec::new()
+        };
+        graph.add_nodes(n);
+        graph
+    }
+}
+
+impl_graph! {
+
+    /// The maximum number of nodes a graph with index type `I` can hold.
+    pub const MAX_NODES: usize = I::MAX / 2;
+
+    /// Returns the strategy used to link components.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::new(10);
+    /// assert_eq!(wuf::Linking::Size, graph.linking());
+    /// ```
+    pub fn linking(&self) -> Linking {
+        self.linking
+    }
+
+    /// Returns the strategy used to compress paths.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::new(10);
+    /// assert_eq!(wuf::Compression::Halving, graph.compression());
+    /// ```
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Returns true only if the members of each component are tracked (see
+    /// `GraphBuilder::track_members`).
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::GraphBuilder::new(10).track_members(true).build();
+    /// assert!(graph.tracks_members());
+    /// assert!(!wuf::Graph::new(10).tracks_members());
+    /// ```
+    pub fn tracks_members(&self) -> bool {
+        self.tracking
+    }
+
+    /// Returns the number of nodes the graph can hold without reallocating.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::with_capacity(0, 10);
+    /// assert!(graph.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        let mut capacity = self.nodes.capacity();
+        if self.linking == Linking::Rank {
+            capacity = capacity.min(self.ranks.capacity());
+        }
+        if self.tracking {
+            capacity = capacity.min(self.next.capacity());
+        }
+        capacity
+    }
+
+    /// Reserves capacity for at least `additional` more nodes.
+    /// 
+    /// # Arguments
+    /// * `additional` Number of nodes to reserve capacity for.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.reserve(10);
+    /// assert!(graph.capacity() >= 20);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+        if self.linking == Linking::Rank {
+            self.ranks.reserve(additional);
+        }
+        if self.tracking {
+            self.next.reserve(additional);
+        }
+    }
+
+    /// Shrinks the capacity of the graph as much as possible.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::with_capacity(10, 100);
+    /// graph.shrink_to_fit();
+    /// assert!(graph.capacity() >= 10);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+        self.next.shrink_to_fit();
+        self.ranks.shrink_to_fit();
+    }
+
+    /// Adds a new node, not connected to any other node, and returns its
+    /// handle, reusing the ID of the node retired last (see `remove_node`)
+    /// if any, in which case the handles of the retired node are no longer
+    /// accepted.
+    /// 
+    /// Panics if the number of nodes would exceed `MAX_NODES`.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let id = graph.add_node();
+    /// assert_eq!(10, id.index());
+    /// assert_eq!(11, graph.count());
+    /// ```
+    pub fn add_node(&mut self) -> NodeId {
+        let index = match self.free.pop() {
+            Some(index) => {
+                let index = index.index();
+                // a retired node cannot be connected, hence it is still alone
+                self.retired[index / 64] &= !(1 << (index % 64));
+                if self.generations.len() <= index {
+                    self.generations.resize(index + 1, 0);
+                }
+                self.generations[index] = self.generations[index].wrapping_add(1);
+                self.components += 1;
+                index
+            }
+            None => self.add_nodes(1).start
+        };
+        self.handle(index)
+    }
+
+    /// Returns the handle of the node with the given raw ID, or `None` if the
+    /// node does not belong to the graph or is retired.
+    /// 
+    /// # Arguments
+    /// * `index` Raw ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::new(10);
+    /// assert_eq!(Some(3), graph.node(3).map(|id| id.index()));
+    /// assert_eq!(None, graph.node(10));
+    /// ```
+    pub fn node(&self, index: usize) -> Option<NodeId> {
+        match self.check(index) {
+            Ok(()) => Some(self.handle(index)),
+            Err(_) => None
+        }
+    }
+
+    /// Returns an iterator over the handles of all nodes but the retired ones,
+    /// in ascending order of raw ID.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::new(10);
+    /// assert_eq!(10, graph.node_ids().count());
+    /// ```
+    pub fn node_ids<'a>(&'a self) -> impl Iterator<Item = NodeId> + 'a {
+        (0..self.nodes.len()).filter_map(move |index| self.node(index))
+    }
+
+    /// Adds `k` new nodes, not connected to any other node, and returns the
+    /// range of their IDs.
+    /// 
+    /// Panics if the number of nodes would exceed `MAX_NODES`.
+    /// 
+    /// # Arguments
+    /// * `k` Number of nodes to add.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let ids = graph.add_nodes(5);
+    /// assert_eq!(10..15, ids);
+    /// assert_eq!(15, graph.count());
+    /// ```
+    pub fn add_nodes(&mut self, k: usize) -> Range<usize> {
+        let start = self.nodes.len();
+        let end = start.checked_add(k).filter(|&end| end <= Self::MAX_NODES)
+            .expect("number of nodes exceeds the capacity of the index type");
+        self.nodes.resize(end, Self::root_entry(1));
+        if self.linking == Linking::Rank {
+            self.ranks.resize(end, 0);
+        }
+        if self.tracking {
+            // every new node is the only member of its component
+            self.next.extend((start..end).map(I::from_usize));
+        }
+        self.components += k;
+        start..end
+    }
+
+    /// Disconnects every node, reusing the existing allocations.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.reset();
+    /// assert!(!graph.connected(0, 1));
+    /// assert_eq!(10, graph.component_count());
+    /// ```
+    pub fn reset(&mut self) {
+        let n = self.nodes.len();
+        self.reset_to(n);
+    }
+
+    /// Resets the graph to the given number of nodes, none connected to any
+    /// other, reusing the existing allocations where possible.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.reset_to(5);
+    /// assert_eq!(5, graph.count());
+    /// assert_eq!(5, graph.component_count());
+    /// ```
+    pub fn reset_to(&mut self, n: usize) {
+        self.nodes.clear();
+        self.ranks.clear();
+        self.next.clear();
+        self.free.clear();
+        self.retired.clear();
+        // generations are kept, so that the handles of reused nodes stay stale
+        self.components = 0;
+        self.pairs = 0;
+        self.add_nodes(n);
+    }
+
+    /// Removes the given node from its component, making it a component of
+    /// its own, and returns true only if the component had other members.
+    /// 
+    /// The rest of the component stays connected, rebuilt as a tree of height
+    /// one, hence this takes time linear in the size of the component if the
+    /// members are tracked, otherwise in the number of nodes (see `members`).
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.connect(1, 2);
+    /// assert!(graph.isolate(1));
+    /// assert!(graph.connected(0, 2));
+    /// assert!(!graph.connected(0, 1));
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn isolate(&mut self, id: usize) -> bool {
+        let others: Vec<usize> = self.members(id).skip(1).collect();
+        let size = others.len();
+        if size == 0 {
+            return false;
+        }
+        // every other member points to the first one, as the new root
+        let root = others[0];
+        for (i, &member) in others.iter().enumerate() {
+            self.nodes[member] = I::from_usize(root);
+            if self.tracking {
+                self.next[member] = I::from_usize(others[(i + 1) % size]);
+            }
+            if self.linking == Linking::Rank {
+                self.ranks[member] = 0;
+            }
+        }
+        self.nodes[root] = Self::root_entry(size);
+        if self.linking == Linking::Rank {
+            self.ranks[root] = (size > 1) as u8;
+            self.ranks[id] = 0;
+        }
+        self.nodes[id] = Self::root_entry(1);
+        if self.tracking {
+            self.next[id] = I::from_usize(id);
+        }
+        self.components += 1;
+        self.pairs -= size;
+        true
+    }
+
+    /// Moves the node `a` into the component of the node `b`, without moving
+    /// the other members of its former component, which stay connected, and
+    /// returns true only if the two nodes were not already connected.
+    /// 
+    /// Takes time linear in the size of the former component of `a` (see
+    /// `isolate`).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the node to move.
+    /// * `b` ID of a node of the destination component.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.connect(0, 1);
+    /// graph.connect(1, 2);
+    /// assert!(graph.move_to(1, 3));
+    /// assert!(graph.connected(1, 3));
+    /// assert!(graph.connected(0, 2));
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn move_to(&mut self, a: usize, b: usize) -> bool {
+        if self.connected(a, b) {
+            return false;
+        }
+        self.isolate(a);
+        self.connect(a, b);
+        true
+    }
+
+    /// Retires the given node, isolating it (see `isolate`) and recording its
+    /// ID to be reused by `add_node`, so that the graph does not grow without
+    /// bound as nodes come and go.
+    /// 
+    /// A retired node is still counted by `count`, but not by
+    /// `component_count`, and it cannot be used until reused: every operation
+    /// on it panics (or returns `WufError::Retired`), and so do its handles,
+    /// even after its ID is reused. Graphs with retired nodes cannot be
+    /// decomposed nor serialized, since their retired IDs would be lost.
+    /// 
+    /// Panics if the node does not belong to the graph or is already retired.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(3, 4);
+    /// graph.remove_node(3);
+    /// assert!(graph.is_retired(3));
+    /// assert_eq!(9, graph.component_count());
+    /// assert!(graph.try_connect(3, 4).is_err());
+    /// assert_eq!(3, graph.add_node().index());
+    /// assert_eq!(10, graph.count());
+    /// assert!(!graph.connected(3, 4));
+    /// ```
+    pub fn remove_node(&mut self, id: usize) {
+        self.assert_in_bounds(id);
+        self.isolate(id);
+        if self.retired.len() <= id / 64 {
+            self.retired.resize(id / 64 + 1, 0);
+        }
+        self.retired[id / 64] |= 1 << (id % 64);
+        self.free.push(I::from_usize(id));
+        self.components -= 1;
+    }
+
+    /// Returns true only if the given node is retired (see `remove_node`), in
+    /// constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.remove_node(3);
+    /// assert!(graph.is_retired(3));
+    /// assert!(!graph.is_retired(4));
+    /// ```
+    pub fn is_retired(&self, id: usize) -> bool {
+        self.retired.get(id / 64).is_some_and(|&word| word & (1 << (id % 64)) != 0)
+    }
+
+    /// Returns the number of retired nodes, waiting to be reused by
+    /// `add_node`.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.remove_node(3);
+    /// assert_eq!(1, graph.retired_count());
+    /// ```
+    pub fn retired_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::Graph::new(10);
+    /// println!("Number of nodes: {}", graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the number of unordered pairs of distinct nodes that are
+    /// connected, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.connect(1, 2);
+    /// graph.connect(3, 4);
+    /// assert_eq!(4, graph.connected_pairs());
+    /// ```
+    pub fn connected_pairs(&self) -> usize {
+        self.pairs
+    }
+
+    /// Returns true only if all the nodes belong to a single component (which
+    /// includes graphs with less than two nodes), in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(3);
+    /// graph.connect(0, 1);
+    /// assert!(!graph.is_fully_connected());
+    /// graph.connect(1, 2);
+    /// assert!(graph.is_fully_connected());
+    /// ```
+    pub fn is_fully_connected(&self) -> bool {
+        self.components <= 1
+    }
+
+    /// Connects the edges of the given iterator, and returns the number of
+    /// edges that merged two components.
+    /// 
+    /// Panics if a node does not belong to the graph.
+    /// 
+    /// # Arguments
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// assert_eq!(2, graph.connect_all(vec![(0, 1), (1, 2), (2, 0)]));
+    /// assert_eq!(2, graph.component_count());
+    /// ```
+    pub fn connect_all<E: IntoIterator<Item = (usize, usize)>>(&mut self, edges: E) -> usize {
+        let components = self.components;
+        for (a, b) in edges {
+            // a single comparison checks the bounds of both nodes
+            self.assert_in_bounds(a.max(b));
+            // safe since both nodes were checked to belong to the graph
+            unsafe { self.connect_unchecked(a, b); }
+        }
+        components - self.components
+    }
+
+    /// Returns whether the two nodes of each of the given pairs are connected,
+    /// in order.
+    /// 
+    /// # Arguments
+    /// * `pairs` The pairs of node IDs to query.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.connect(0, 1);
+    /// assert_eq!(vec![true, false], graph.connected_batch(&[(1, 0), (2, 3)]));
+    /// ```
+    pub fn connected_batch(&mut self, pairs: &[(usize, usize)]) -> Vec<bool> {
+        pairs.iter().map(|&(a, b)| self.connected(a, b)).collect()
+    }
+
+    /// Returns the root of each of the given nodes, in order.
+    /// 
+    /// # Arguments
+    /// * `ids` The IDs of the nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.connect(0, 1);
+    /// assert_eq!(vec![0, 0, 2], graph.roots_of(&[0, 1, 2]));
+    /// ```
+    pub fn roots_of(&mut self, ids: &[usize]) -> Vec<usize> {
+        ids.iter().map(|&id| self.find(id)).collect()
+    }
+
+    /// Connects the edges of the given iterator until the graph is fully
+    /// connected (see `is_fully_connected`), without consuming the remaining
+    /// edges, and returns the number of edges consumed.
+    /// 
+    /// # Arguments
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(3);
+    /// let mut edges = vec![(0, 1), (1, 0), (2, 1), (2, 0)].into_iter();
+    /// assert_eq!(3, graph.connect_until_connected(&mut edges));
+    /// assert!(graph.is_fully_connected());
+    /// assert_eq!(Some((2, 0)), edges.next());
+    /// ```
+    pub fn connect_until_connected<E: IntoIterator<Item = (usize, usize)>>(&mut self, edges: E) -> usize {
+        let mut consumed = 0;
+        let mut edges = edges.into_iter();
+        while !self.is_fully_connected() {
+            match edges.next() {
+                Some((a, b)) => {
+                    self.connect(a, b);
+                    consumed += 1;
+                }
+                None => break
+            }
+        }
+        consumed
+    }
+
+    /// Connects the edges of the given iterator, and returns the edges that
+    /// merged two components, in order, which form a spanning forest of the
+    /// edges (together with the connections made before).
+    /// 
+    /// # Arguments
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// let forest = graph.spanning_forest(vec![(0, 1), (1, 2), (2, 0), (3, 3)]);
+    /// assert_eq!(vec![(0, 1), (1, 2)], forest);
+    /// ```
+    pub fn spanning_forest<E>(&mut self, edges: E) -> Vec<(usize, usize)>
+    where
+        E: IntoIterator<Item = (usize, usize)>
+    {
+        edges.into_iter().filter(|&(a, b)| self.connect(a, b).is_some()).collect()
+    }
+
+    /// Connects the edges of the given iterator, and returns the number of
+    /// redundant edges, whose nodes were already connected.
+    /// 
+    /// # Arguments
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// assert_eq!(2, graph.redundant_edges(vec![(0, 1), (1, 2), (2, 0), (3, 3)]));
+    /// ```
+    pub fn redundant_edges<E: IntoIterator<Item = (usize, usize)>>(&mut self, edges: E) -> usize {
+        edges.into_iter().filter(|&(a, b)| self.connect(a, b).is_none()).count()
+    }
+
+    /// Connects the edges of the given iterator, and returns the number of
+    /// redundant edges (see `redundant_edges`) of every component that has
+    /// any, by root of the component once all the edges are connected.
+    /// 
+    /// # Arguments
+    /// * `edges` The edges to connect, as pairs of node IDs.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(5);
+    /// let redundant = graph.redundant_edges_by_component(vec![(0, 1), (1, 0), (2, 3), (3, 2), (0, 2)]);
+    /// let root = graph.find(0);
+    /// assert_eq!(Some(&2), redundant.get(&root));
+    /// assert_eq!(1, redundant.len());
+    /// ```
+    pub fn redundant_edges_by_component<E>(&mut self, edges: E) -> BTreeMap<usize, usize>
+    where
+        E: IntoIterator<Item = (usize, usize)>
+    {
+        // roots may change while connecting, hence look them up at the end
+        let redundant: Vec<usize> = edges.into_iter()
+            .filter(|&(a, b)| self.connect(a, b).is_none())
+            .map(|(a, _)| a)
+            .collect();
+        let mut counts = BTreeMap::new();
+        for id in redundant {
+            *counts.entry(self.find(id)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(2, graph.component_size(1));
+    /// assert_eq!(1, graph.component_size(2));
+    /// ```
+    pub fn component_size(&mut self, id: usize) -> usize {
+        let root = self.find(id);
+        self.size_of(root)
+    }
+
+    /// Returns the root and the number of nodes of the largest component, in
+    /// time linear in the number of nodes, or `None` if the graph is empty.
+    /// Ties are broken in favor of the smallest root.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(4, 5);
+    /// graph.connect(5, 6);
+    /// assert_eq!(Some((4, 3)), graph.largest_component());
+    /// ```
+    pub fn largest_component(&self) -> Option<(usize, usize)> {
+        self.roots().fold(None, |largest, root| {
+            let size = self.size_of(root);
+            match largest {
+                Some((_, largest_size)) if largest_size >= size => largest,
+                _ => Some((root, size))
+            }
+        })
+    }
+
+    /// Returns the number of components of every size, in ascending order of
+    /// size.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(5);
+    /// graph.connect(0, 1);
+    /// graph.connect(2, 3);
+    /// let histogram: Vec<_> = graph.size_histogram().into_iter().collect();
+    /// assert_eq!(vec![(1, 1), (2, 2)], histogram);
+    /// ```
+    pub fn size_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for root in self.roots() {
+            *histogram.entry(self.size_of(root)).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Returns an iterator over the members of the component the given node
+    /// belongs to, starting from the node itself.
+    /// 
+    /// If the members are tracked (see `GraphBuilder::track_members`), every
+    /// component keeps its members in a circular list, where two lists are
+    /// spliced in constant time when their components are connected, hence
+    /// this takes time linear in the size of the component. Otherwise, this
+    /// scans every node, in ascending order of ID after the given one.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.connect(5, 1);
+    /// let mut members: Vec<usize> = graph.members(1).collect();
+    /// members.sort();
+    /// assert_eq!(vec![0, 1, 5], members);
+    /// ```
+    pub fn members<'a>(&'a self, id: usize) -> impl Iterator<Item = usize> + 'a {
+        self.assert_in_bounds(id);
+        let root = if self.tracking { id } else { self.find_ref(id) };
+        let n = self.nodes.len();
+        iter::successors(Some(id), move |&node| {
+            if self.tracking {
+                let next = self.next[node].index();
+                return if next == id { None } else { Some(next) };
+            }
+            // scan the nodes after this one, wrapping around to the given one
+            let mut others = if node >= id { (node + 1..n).chain(0..id) } else { (node + 1..id).chain(0..0) };
+            others.find(|&other| !self.is_retired(other) && self.find_ref(other) == root)
+        })
+    }
+
+    /// Returns an iterator over the components, in ascending order of root
+    /// ID, each as an iterator over its members starting from the root (see
+    /// `members`), visiting every node once overall.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.connect(0, 1);
+    /// graph.connect(3, 1);
+    /// let mut sizes: Vec<usize> = graph.components().map(|members| members.count()).collect();
+    /// sizes.sort();
+    /// assert_eq!(vec![1, 3], sizes);
+    /// ```
+    pub fn components<'a>(&'a self) -> impl Iterator<Item = impl Iterator<Item = usize> + 'a> + 'a {
+        // without circular lists, the members of every component are gathered
+        // at once, rather than scanning every node for each component
+        let mut gathered = Vec::new();
+        if !self.tracking {
+            gathered = vec![Vec::new(); self.nodes.len()];
+            for root in self.roots() {
+                gathered[root].push(root);
+            }
+            for id in (0..self.nodes.len()).filter(|&id| !self.is_retired(id)) {
+                let root = self.find_ref(id);
+                if root != id {
+                    gathered[root].push(id);
+                }
+            }
+        }
+        self.roots().map(move |root| {
+            let members = if self.tracking {
+                self.members(root).collect()
+            } else {
+                mem::take(&mut gathered[root])
+            };
+            members.into_iter()
+        })
+    }
+
+    /// Returns an iterator over the root of every component, in ascending
+    /// order of ID, leaving out the retired nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.connect(0, 1);
+    /// graph.connect(3, 2);
+    /// assert_eq!(2, graph.roots().count());
+    /// assert!(graph.roots().all(|root| graph.find_ref(root) == root));
+    /// ```
+    pub fn roots<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+        self.nodes.iter().enumerate()
+            .filter(move |&(root, &entry)| Self::is_root_entry(entry) && !self.is_retired(root))
+            .map(|(root, _)| root)
+    }
+
+    /// Returns the label of every node, where components are labeled from 0
+    /// to `component_count() - 1` in order of their smallest node ID, and
+    /// retired nodes are labeled `usize::MAX`.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(5);
+    /// graph.connect(4, 1);
+    /// graph.connect(3, 0);
+    /// assert_eq!(vec![0, 1, 2, 0, 1], graph.labels());
+    /// ```
+    pub fn labels(&self) -> Vec<usize> {
+        const UNLABELED: usize = usize::MAX;
+        let mut labels = vec![UNLABELED; self.nodes.len()];
+        let mut next = 0;
+        for id in 0..self.nodes.len() {
+            if self.is_retired(id) {
+                continue;
+            }
+            let root = self.find_ref(id);
+            // the root is labeled when the first node of its component is visited
+            if labels[root] == UNLABELED {
+                labels[root] = next;
+                next += 1;
+            }
+            labels[id] = labels[root];
+        }
+        labels
+    }
+
+    /// Consumes the graph and returns its components as lists of nodes in
+    /// ascending order of ID, in order of smallest ID.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(5);
+    /// graph.connect(3, 0);
+    /// graph.connect(1, 2);
+    /// assert_eq!(vec![vec![0, 3], vec![1, 2], vec![4]], graph.into_sets());
+    /// ```
+    pub fn into_sets(self) -> Vec<Vec<usize>> {
+        self.groups()
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let node1 = 0;
+    /// let node2 = 1;
+    /// println!("Are nodes connected? {}", graph.connected(node1, node2));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.assert_in_bounds(a);
+        self.assert_in_bounds(b);
+        // safe since both nodes were checked to belong to the graph
+        unsafe { self.connected_unchecked(a, b) }
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None` (the nodes were already
+    /// connected and nothing changed).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let node1 = 0;
+    /// let node2 = 1;
+    /// let root = graph.connect(node1, node2);
+    /// assert_eq!(root, Some(graph.find(node1)));
+    /// assert_eq!(None, graph.connect(node2, node1));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        self.assert_in_bounds(a);
+        self.assert_in_bounds(b);
+        // safe since both nodes were checked to belong to the graph
+        unsafe { self.connect_unchecked(a, b) }
+    }
+
+    /// Same as `find`, but takes and returns node handles.
+    /// 
+    /// Panics if the node does not belong to this graph (or to a clone of it).
+    /// 
+    /// # Arguments
+    /// * `id` Handle of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let id = graph.node(3).unwrap();
+    /// assert_eq!(id, graph.find_node(id));
+    /// ```
+    pub fn find_node(&mut self, id: NodeId) -> NodeId {
+        let root = self.find(self.index_of(id));
+        self.handle(root)
+    }
+
+    /// Same as `connected`, but takes node handles.
+    /// 
+    /// Panics if any of the nodes does not belong to this graph (or to a clone
+    /// of it).
+    /// 
+    /// # Arguments
+    /// * `a` Handle of the first node.
+    /// * `b` Handle of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let a = graph.node(0).unwrap();
+    /// let b = graph.node(1).unwrap();
+    /// assert!(!graph.connected_nodes(a, b));
+    /// ```
+    pub fn connected_nodes(&mut self, a: NodeId, b: NodeId) -> bool {
+        let a = self.index_of(a);
+        let b = self.index_of(b);
+        self.connected(a, b)
+    }
+
+    /// Same as `connect`, but takes and returns node handles.
+    /// 
+    /// Panics if any of the nodes does not belong to this graph (or to a clone
+    /// of it).
+    /// 
+    /// # Arguments
+    /// * `a` Handle of the first node.
+    /// * `b` Handle of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// let a = graph.node(0).unwrap();
+    /// let b = graph.add_node();
+    /// let root = graph.connect_nodes(a, b).unwrap();
+    /// assert_eq!(root, graph.find_node(b));
+    /// ```
+    pub fn connect_nodes(&mut self, a: NodeId, b: NodeId) -> Option<NodeId> {
+        let a = self.index_of(a);
+        let b = self.index_of(b);
+        self.connect(a, b).map(|root| self.handle(root))
+    }
+
+    /// Same as `find`, but returns an error instead of panicking if the given
+    /// node does not belong to the graph.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert_eq!(Ok(3), graph.try_find(3));
+    /// assert!(graph.try_find(10).is_err());
+    /// ```
+    pub fn try_find(&mut self, id: usize) -> Result<usize, WufError> {
+        self.check(id)?;
+        Ok(self.find(id))
+    }
+
+    /// Same as `connected`, but returns an error instead of panicking if any
+    /// of the given nodes does not belong to the graph.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert_eq!(Ok(false), graph.try_connected(0, 1));
+    /// assert!(graph.try_connected(0, 10).is_err());
+    /// ```
+    pub fn try_connected(&mut self, a: usize, b: usize) -> Result<bool, WufError> {
+        self.check(a)?;
+        self.check(b)?;
+        Ok(self.connected(a, b))
+    }
+
+    /// Same as `connect`, but returns an error instead of panicking if any
+    /// of the given nodes does not belong to the graph.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert!(graph.try_connect(0, 1).unwrap().is_some());
+    /// assert!(graph.try_connect(0, 10).is_err());
+    /// ```
+    pub fn try_connect(&mut self, a: usize, b: usize) -> Result<Option<usize>, WufError> {
+        self.check(a)?;
+        self.check(b)?;
+        Ok(self.connect(a, b))
+    }
+
+    /// Returns the root of the given node, that is the representative of the
+    /// component the node belongs to. Two nodes are connected only if they
+    /// have the same root.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        self.assert_in_bounds(id);
+        // safe since the node was checked to belong to the graph
+        unsafe { self.find_unchecked(id) }
+    }
+
+    /// Same as `find`, but without compressing the path, so that it only needs
+    /// a shared reference to the graph.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// let graph = &graph;
+    /// assert_eq!(graph.find_ref(0), graph.find_ref(1));
+    /// ```
+    pub fn find_ref(&self, id: usize) -> usize {
+        self.assert_in_bounds(id);
+        // safe since the node was checked to belong to the graph
+        unsafe { self.find_root_unchecked(id) }
+    }
+
+    /// Same as `connected`, but without compressing the paths, so that it only
+    /// needs a shared reference to the graph.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// let graph = &graph;
+    /// assert!(graph.connected_ref(0, 1));
+    /// assert!(!graph.connected_ref(0, 2));
+    /// ```
+    pub fn connected_ref(&self, a: usize, b: usize) -> bool {
+        self.find_ref(a) == self.find_ref(b)
+    }
+
+    /// Makes every node point directly to its root, in time linear in the
+    /// number of nodes (whatever the compression strategy), so that
+    /// subsequent finds take constant time until the next connection.
+    /// 
+    /// Every path walked is compressed completely, so that each node is
+    /// walked past at most once before pointing to its root.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.connect(1, 2);
+    /// graph.flatten();
+    /// assert!(graph.connected(0, 2));
+    /// ```
+    pub fn flatten(&mut self) {
+        for id in 0..self.nodes.len() {
+            // safe since every ID lower than the number of nodes is valid
+            unsafe {
+                let root = self.find_root_unchecked(id);
+                let mut node = id;
+                while node != root {
+                    let parent = self.entry(node).index();
+                    self.set_entry(node, I::from_usize(root));
+                    node = parent;
+                }
+            }
+        }
+    }
+
+    /// Same as `find`, but without checking that the node belongs to the
+    /// graph.
+    /// 
+    /// # Safety
+    /// The node ID must be lower than `count()`.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert_eq!(3, unsafe { graph.find_unchecked(3) });
+    /// ```
+    pub unsafe fn find_unchecked(&mut self, id: usize) -> usize {
+        // every parent stored in the graph belongs to the graph, hence once the
+        // first node is valid so is every other node in its path
+        match self.compression {
+            Compression::Full => {
+                let root = self.find_root_unchecked(id);
+                // make every node in path point to the root
+                let mut node = id;
+                while node != root {
+                    let parent = self.entry(node).index();
+                    self.set_entry(node, I::from_usize(root));
+                    node = parent;
+                }
+                root
+            }
+            Compression::Halving | Compression::Splitting => {
+                let halving = self.compression == Compression::Halving;
+                let mut node = id;
+                loop {
+                    let parent = self.entry(node);
+                    if Self::is_root_entry(parent) {
+                        return node;
+                    }
+                    let grandparent = self.entry(parent.index());
+                    if Self::is_root_entry(grandparent) {
+                        return parent.index();
+                    }
+                    // make the node point to its grandparent, then either skip
+                    // to the grandparent (halving) or visit the parent as well
+                    // (splitting)
+                    self.set_entry(node, grandparent);
+                    node = if halving { grandparent.index() } else { parent.index() };
+                }
+            }
+            Compression::None => self.find_root_unchecked(id),
+        }
+    }
+
+    /// Same as `connected`, but without checking that the nodes belong to the
+    /// graph.
+    /// 
+    /// # Safety
+    /// Both node IDs must be lower than `count()`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert!(!unsafe { graph.connected_unchecked(0, 1) });
+    /// ```
+    pub unsafe fn connected_unchecked(&mut self, a: usize, b: usize) -> bool {
+        // check if the two nodes have the same root
+        self.find_unchecked(a) == self.find_unchecked(b)
+    }
+
+    /// Same as `connect`, but without checking that the nodes belong to the
+    /// graph.
+    /// 
+    /// Still panics if any of the nodes is retired (see `remove_node`), which
+    /// only takes a comparison as long as no node is.
+    /// 
+    /// # Safety
+    /// Both node IDs must be lower than `count()`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(10);
+    /// assert!(unsafe { graph.connect_unchecked(0, 1) }.is_some());
+    /// ```
+    pub unsafe fn connect_unchecked(&mut self, a: usize, b: usize) -> Option<usize> {
+        if !self.free.is_empty() && (self.is_retired(a) || self.is_retired(b)) {
+            panic!("{}", WufError::Retired { id: if self.is_retired(a) { a } else { b } });
+        }
+        let a_root = self.find_unchecked(a);
+        let b_root = self.find_unchecked(b);
+        if a_root == b_root {
+            // already connected
+            return None;
+        }
+        let a_size = self.size_of(a_root);
+        let b_size = self.size_of(b_root);
+        let (root, child) = match self.linking {
+            // balance by linking root of smaller tree to root of larger tree
+            Linking::Size if a_size < b_size => (b_root, a_root),
+            Linking::Size => (a_root, b_root),
+            // balance by linking root of lower rank to root of higher rank
+            Linking::Rank => {
+                let a_rank = *self.ranks.get_unchecked(a_root);
+                let b_rank = *self.ranks.get_unchecked(b_root);
+                if a_rank < b_rank {
+                    (b_root, a_root)
+                } else {
+                    if a_rank == b_rank {
+                        *self.ranks.get_unchecked_mut(a_root) += 1;
+                    }
+                    (a_root, b_root)
+                }
+            }
+        };
+        self.set_entry(child, I::from_usize(root));
+        self.set_entry(root, Self::root_entry(a_size + b_size));
+        if self.tracking {
+            // splice the circular lists of members of the two components
+            let root_next = *self.next.get_unchecked(root);
+            *self.next.get_unchecked_mut(root) = *self.next.get_unchecked(child);
+            *self.next.get_unchecked_mut(child) = root_next;
+        }
+        self.components -= 1;
+        self.pairs += a_size * b_size;
+        Some(root)
+    }
+
+    /// Returns the root of the given node without modifying any path, and
+    /// without checking that the node belongs to the graph.
+    unsafe fn find_root_unchecked(&self, id: usize) -> usize {
+        let mut node = id;
+        loop {
+            let parent = self.entry(node);
+            if Self::is_root_entry(parent) {
+                return node;
+            }
+            node = parent.index();
+        }
+    }
+
+    /// Returns the entry of the given node, without bounds checking.
+    #[inline]
+    unsafe fn entry(&self, id: usize) -> I {
+        *self.nodes.get_unchecked(id)
+    }
+
+    /// Sets the entry of the given node, without bounds checking.
+    #[inline]
+    unsafe fn set_entry(&mut self, id: usize, entry: I) {
+        *self.nodes.get_unchecked_mut(id) = entry;
+    }
+
+    /// Returns the size of the tree rooted at the given node.
+    pub(crate) fn size_of(&self, root: usize) -> usize {
+        I::MAX - self.nodes[root].index()
+    }
+
+    /// Returns the entry stored by a root whose tree has the given size.
+    fn root_entry(size: usize) -> I {
+        I::from_usize(I::MAX - size)
+    }
+
+    /// Returns true if the given entry is the encoded size of a root rather
+    /// than the ID of a parent.
+    pub(crate) fn is_root_entry(entry: I) -> bool {
+        entry.index() > Self::MAX_NODES
+    }
+
+    /// Returns the raw ID of the given node, panicking if the node does not
+    /// belong to this graph, or was retired since the handle was obtained.
+    fn index_of(&self, id: NodeId) -> usize {
+        assert_eq!(self.tag, id.tag(), "node {:?} belongs to a different graph", id);
+        assert_eq!(self.generation(id.index()), id.generation(), "node {:?} has been removed", id);
+        id.index()
+    }
+
+    /// Returns the handle of the given node.
+    fn handle(&self, id: usize) -> NodeId {
+        NodeId::new(id, self.tag, self.generation(id))
+    }
+
+    /// Returns the number of times the given node was reused.
+    fn generation(&self, id: usize) -> u32 {
+        self.generations.get(id).cloned().unwrap_or(0)
+    }
+
+    /// Panics if the given node does not belong to the graph.
+    #[inline]
+    fn assert_in_bounds(&self, id: usize) {
+        if let Err(err) = self.check(id) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Returns the components as lists of nodes in ascending order of ID, in
+    /// order of smallest ID, as a JSON array of arrays.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(5);
+    /// graph.connect(3, 0);
+    /// graph.connect(1, 2);
+    /// assert_eq!("[[0,3],[1,2],[4]]", graph.to_json_components());
+    /// ```
+    pub fn to_json_components(&self) -> String {
+        let groups = self.groups();
+        let mut json = String::with_capacity(2 + 4 * self.nodes.len());
+        json.push('[');
+        for (i, group) in groups.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push('[');
+            for (j, id) in group.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                json.push_str(&id.to_string());
+            }
+            json.push(']');
+        }
+        json.push(']');
+        json
+    }
+
+    /// Returns the nodes grouped by component, each group in ascending order
+    /// of ID, and the groups in order of smallest ID.
+    pub(crate) fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = vec![Vec::new(); self.components];
+        for (id, label) in self.labels().into_iter().enumerate() {
+            if let Some(group) = groups.get_mut(label) {
+                group.push(id);
+            }
+        }
+        groups
+    }
+
+    /// Returns the parent of each node, where roots are their own parent, and
+    /// the size of the tree rooted at each node, which is 0 for non-roots.
+    #[cfg(any(all(test, feature = "std"), feature = "serde"))]
+    pub(crate) fn parents_and_sizes(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut parents = Vec::with_capacity(self.nodes.len());
+        let mut sizes = Vec::with_capacity(self.nodes.len());
+        for (id, &entry) in self.nodes.iter().enumerate() {
+            if Self::is_root_entry(entry) {
+                parents.push(id);
+                sizes.push(I::MAX - entry.index());
+            } else {
+                parents.push(entry.index());
+                sizes.push(0);
+            }
+        }
+        (parents, sizes)
+    }
+
+    /// Returns an iterator over the parent of each node, where roots are their
+    /// own parent.
+    #[cfg(feature = "std")]
+    pub(crate) fn parents<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+        self.nodes.iter().enumerate().map(|(id, &entry)| {
+            if Self::is_root_entry(entry) { id } else { entry.index() }
+        })
+    }
+
+    /// Returns the ranks of the nodes, which are empty unless linking by rank.
+    #[cfg(any(feature = "std", feature = "serde"))]
+    pub(crate) fn ranks(&self) -> &[u8] {
+        &self.ranks
+    }
+
+    /// Starts tracking the members of each component of the graph, whose
+    /// nodes must all be disconnected.
+    pub(crate) fn track_members(&mut self) {
+        debug_assert_eq!(self.nodes.len(), self.components + self.free.len());
+        self.tracking = true;
+        self.next.clear();
+        self.next.reserve(self.nodes.capacity());
+        self.next.extend((0..self.nodes.len()).map(I::from_usize));
+    }
+
+    /// Returns an error if the given node does not belong to the graph, or is
+    /// retired.
+    fn check(&self, id: usize) -> Result<(), WufError> {
+        if id >= self.nodes.len() {
+            Err(WufError::OutOfBounds { id, count: self.nodes.len() })
+        } else if self.is_retired(id) {
+            Err(WufError::Retired { id })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns an error if any node is retired, since the arrays of the graph
+    /// do not record which ones.
+    pub(crate) fn check_not_retired(&self) -> Result<(), WufError> {
+        match self.free.first() {
+            Some(&id) => Err(WufError::Retired { id: id.index() }),
+            None => Ok(())
+        }
+    }
+}
+
+/// Returns the number of unordered pairs of distinct nodes in a component of
+/// the given size.
+fn pairs_within(size: usize) -> usize {
+    size * (size - 1) / 2
+}
+
+impl_graph! {
+    impl fmt::Debug {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            /// Formats a slice of node IDs as a set.
+            struct Set<'a>(&'a [usize]);
+
+            impl<'a> fmt::Debug for Set<'a> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.debug_set().entries(self.0).finish()
+                }
+            }
+
+            let groups = self.groups();
+            f.debug_set().entries(groups.iter().map(|group| Set(group))).finish()
+        }
+    }
+}
+
+impl_graph! {
+    impl UnionFind {
+        fn len(&self) -> usize {
+            self.count()
+        }
+
+        fn find(&mut self, id: usize) -> usize {
+            Graph::find(self, id)
+        }
+
+        fn union(&mut self, a: usize, b: usize) -> bool {
+            self.connect(a, b).is_some()
+        }
+
+        fn connected(&mut self, a: usize, b: usize) -> bool {
+            Graph::connected(self, a, b)
+        }
+    }
+}
+
+impl_graph! {
+    /// Connects every edge of the iterator, as with `connect_all`.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::Graph::new(4);
+    /// graph.extend((0..3).map(|id| (id, id + 1)));
+    /// assert_eq!(1, graph.component_count());
+    /// ```
+    impl Extend<(usize, usize)> {
+        fn extend<E: IntoIterator<Item = (usize, usize)>>(&mut self, edges: E) {
+            self.connect_all(edges);
+        }
+    }
+}
+
+/// Collects the edges into a new graph, whose number of nodes is one more than
+/// the largest node ID (see `from_edges` to set the number of nodes).
+/// 
+/// # Example
+/// ```
+/// let graph: wuf::Graph = vec![(0, 1), (3, 4)].into_iter().collect();
+/// assert_eq!(5, graph.count());
+/// assert_eq!(3, graph.component_count());
+/// ```
+impl<I: Index> iter::FromIterator<(usize, usize)> for Graph<I> {
+    fn from_iter<E: IntoIterator<Item = (usize, usize)>>(edges: E) -> Graph<I> {
+        let edges: Vec<(usize, usize)> = edges.into_iter().collect();
+        let n = edges.iter().map(|&(a, b)| a.max(b) + 1).max().unwrap_or(0);
+        let mut graph = Graph::with_nodes(n);
+        graph.connect_all(edges);
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_get_count() {
+        let n = 10;
+        let graph = Graph::new(n);
+        assert_eq!(n, graph.count());
+    }
+
+    #[test]
+    fn should_connect() {
+        let mut graph = Graph::new(10);
+        assert!(graph.connected(0, 0));
+        assert!(!graph.connected(0, 1));
+        graph.connect(0, 1);
+        assert!(graph.connected(0, 1));
+    }
+
+    #[test]
+    fn should_count_components() {
+        let mut graph = Graph::new(5);
+        assert_eq!(5, graph.component_count());
+        graph.connect(0, 1);
+        graph.connect(1, 0);
+        assert_eq!(4, graph.component_count());
+        graph.connect(2, 3);
+        graph.connect(0, 3);
+        assert_eq!(2, graph.component_count());
+    }
+
+    #[test]
+    fn should_get_component_size() {
+        let mut graph = Graph::new(5);
+        assert_eq!(1, graph.component_size(0));
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.connect(3, 1);
+        assert!((0..4).all(|id| graph.component_size(id) == 4));
+        assert_eq!(1, graph.component_size(4));
+    }
+
+    #[test]
+    fn should_report_merge() {
+        let mut graph = Graph::new(3);
+        let root = graph.connect(0, 1).unwrap();
+        assert_eq!(root, graph.find(0));
+        assert_eq!(root, graph.find(1));
+        assert_eq!(None, graph.connect(1, 0));
+        // the root of the larger component survives
+        assert_eq!(Some(root), graph.connect(2, 1));
+    }
+
+    #[test]
+    fn should_fail_out_of_bounds() {
+        let mut graph = Graph::new(3);
+        let err = WufError::OutOfBounds { id: 3, count: 3 };
+        assert_eq!(Err(err.clone()), graph.try_find(3));
+        assert_eq!(Err(err.clone()), graph.try_connected(0, 3));
+        assert_eq!(Err(err), graph.try_connect(3, 0));
+        assert_eq!(3, graph.component_count());
+        assert!(graph.try_connect(0, 2).unwrap().is_some());
+        assert_eq!(Ok(true), graph.try_connected(2, 0));
+    }
+
+    #[test]
+    fn should_add_node() {
+        let mut graph = Graph::new(2);
+        graph.connect(0, 1);
+        let id = graph.add_node().index();
+        assert_eq!(2, id);
+        assert_eq!(3, graph.count());
+        assert_eq!(2, graph.component_count());
+        assert!(!graph.connected(0, id));
+        graph.connect(id, 1);
+        assert_eq!(3, graph.component_size(0));
+    }
+
+    #[test]
+    fn should_iterate_members() {
+        let sorted = |graph: &Graph<u16>, id: usize| {
+            let mut members: Vec<usize> = graph.members(id).collect();
+            members.sort();
+            members
+        };
+        for &(linking, tracking) in &[(Linking::Size, false), (Linking::Size, true), (Linking::Rank, true)] {
+            let mut graph = Graph::<u16>::with_config(8, 8, linking, Compression::Full);
+            if tracking {
+                graph.track_members();
+            }
+            for &(a, b) in &[(0, 4), (6, 2), (4, 2), (1, 7), (2, 0)] {
+                graph.connect(a, b);
+            }
+            assert_eq!(tracking, graph.tracks_members());
+            assert_eq!(vec![0, 2, 4, 6], sorted(&graph, 6));
+            assert_eq!(vec![1, 7], sorted(&graph, 1));
+            assert_eq!(vec![3], sorted(&graph, 3));
+            assert_eq!(Some(5), graph.members(5).next());
+            assert_eq!(Some(6), graph.members(6).next());
+            let mut sizes: Vec<usize> = graph.components().map(Iterator::count).collect();
+            sizes.sort();
+            assert_eq!(vec![1, 1, 2, 4], sizes);
+            let (nodes, ranks) = graph.clone().into_raw_parts();
+            let other = Graph::try_from_raw_parts(nodes, ranks, linking, Compression::Full).unwrap();
+            assert!((0..8).all(|id| sorted(&graph, id) == sorted(&other, id)));
+            let other = unsafe { Graph::from_raw_parts(other.nodes, other.ranks, linking, Compression::Full) };
+            assert!((0..8).all(|id| sorted(&graph, id) == sorted(&other, id)));
+            graph.reset();
+            assert_eq!(tracking, graph.tracks_members());
+            assert_eq!(vec![6], sorted(&graph, 6));
+        }
+    }
+
+    #[test]
+    fn should_iterate_components() {
+        let mut graph = Graph::new(7);
+        for &(a, b) in &[(5, 1), (1, 3), (6, 4)] {
+            graph.connect(a, b);
+        }
+        let mut components: Vec<Vec<usize>> = graph.components().map(|members| {
+            let mut members: Vec<usize> = members.collect();
+            members.sort();
+            members
+        }).collect();
+        components.sort();
+        assert_eq!(vec![vec![0], vec![1, 3, 5], vec![2], vec![4, 6]], components);
+        assert_eq!(0, Graph::new(0).components().count());
+        let roots: Vec<usize> = graph.roots().collect();
+        assert_eq!(vec![0, 2, 5, 6], roots);
+    }
+
+    #[test]
+    fn should_measure_components() {
+        let mut graph = Graph::new(7);
+        assert_eq!(Some((0, 1)), graph.largest_component());
+        for &(a, b) in &[(5, 1), (6, 4), (1, 3)] {
+            graph.connect(a, b);
+        }
+        assert_eq!(Some((5, 3)), graph.largest_component());
+        let histogram: Vec<_> = graph.size_histogram().into_iter().collect();
+        assert_eq!(vec![(1, 2), (2, 1), (3, 1)], histogram);
+        assert_eq!(None, Graph::new(0).largest_component());
+        assert!(Graph::new(0).size_histogram().is_empty());
+    }
+
+    #[test]
+    fn should_count_connected_pairs() {
+        let mut graph = Graph::new(7);
+        assert_eq!(0, graph.connected_pairs());
+        for &(a, b) in &[(5, 1), (6, 4), (1, 3)] {
+            graph.connect(a, b);
+        }
+        assert_eq!(3 + 1, graph.connected_pairs());
+        graph.connect(4, 3);
+        assert_eq!(10, graph.connected_pairs());
+    }
+
+    #[test]
+    fn should_detect_full_connectivity() {
+        let mut graph = Graph::from_edges(7, vec![(5, 1), (6, 4), (1, 3)]);
+        assert!(!graph.is_fully_connected());
+        assert_eq!(2, graph.connect_until_connected(vec![(0, 1), (3, 4)]));
+        assert_eq!(1, graph.connect_until_connected(vec![(2, 6), (1, 3), (0, 2)]));
+        assert!(graph.is_fully_connected());
+        assert!(Graph::new(0).is_fully_connected());
+    }
+
+    #[test]
+    fn should_extract_spanning_forest() {
+        let mut graph = Graph::new(5);
+        let forest = graph.spanning_forest(vec![(4, 3), (0, 1), (3, 4), (1, 3), (0, 4), (2, 2)]);
+        assert_eq!(vec![(4, 3), (0, 1), (1, 3)], forest);
+        assert_eq!(forest.len(), graph.count() - graph.component_count());
+    }
+
+    #[test]
+    fn should_count_redundant_edges() {
+        let mut graph = Graph::new(6);
+        let edges = vec![(0, 1), (1, 0), (4, 5), (5, 4), (4, 5), (2, 3), (3, 1), (0, 2)];
+        assert_eq!(4, graph.clone().redundant_edges(edges.clone()));
+        let redundant = graph.redundant_edges_by_component(edges);
+        let counts: Vec<_> = redundant.into_iter().map(|(root, count)| (graph.component_size(root), count)).collect();
+        assert_eq!(vec![(4, 2), (2, 2)], counts);
+    }
+
+    #[test]
+    fn should_connect_all() {
+        let forest = vec![(4, 3), (0, 1), (1, 3)];
+        assert_eq!(4, Graph::new(5).connect_all(forest.into_iter().chain(vec![(1, 4), (2, 0)])));
+        assert_eq!(0, Graph::new(5).connect_all(vec![(2, 2)]));
+    }
+
+    #[test]
+    fn should_query_in_batch() {
+        let mut graph = Graph::from_edges(5, vec![(4, 3), (0, 1), (1, 3)]);
+        assert_eq!(vec![true, false, true], graph.connected_batch(&[(0, 3), (2, 4), (1, 4)]));
+        assert_eq!(vec![graph.find(0); 4], graph.roots_of(&[3, 1, 0, 4]));
+        assert!(graph.connected_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_extend() {
+        let mut extended = Graph::new(5);
+        extended.extend(vec![(0, 1), (2, 4)]);
+        extended.extend(iter::once((1, 3)));
+        assert_eq!(2, extended.component_count());
+    }
+
+    #[test]
+    fn should_collect_edges() {
+        let collected: Graph<u8> = vec![(2, 6), (6, 4)].into_iter().collect();
+        assert_eq!(7, collected.count());
+        assert!(collected.same_partition(&Graph::from_edges(7, vec![(4, 2), (2, 6)])));
+        assert_eq!(0, iter::empty().collect::<Graph>().count());
+    }
+
+    #[test]
+    fn should_add_nodes() {
+        let mut graph = Graph::new(2);
+        assert_eq!(2..2, graph.add_nodes(0));
+        let ids = graph.add_nodes(3);
+        assert_eq!(2..5, ids);
+        assert_eq!(5, graph.count());
+        assert_eq!(5, graph.component_count());
+        graph.connect(0, 4);
+        assert_eq!(2, graph.component_size(4));
+    }
+
+    #[test]
+    fn should_reset() {
+        let mut graph = Graph::new(4);
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.reset();
+        assert_eq!(4, graph.count());
+        assert_eq!(4, graph.component_count());
+        assert!((0..4).all(|id| graph.find(id) == id && graph.component_size(id) == 1));
+        graph.reset_to(6);
+        assert_eq!(6, graph.count());
+        assert_eq!(6, graph.component_count());
+        graph.reset_to(1);
+        assert_eq!(1, graph.count());
+    }
+
+    #[test]
+    fn should_manage_capacity() {
+        let mut graph = Graph::with_capacity(2, 8);
+        assert_eq!(2, graph.count());
+        assert_eq!(2, graph.component_count());
+        assert!(graph.capacity() >= 8);
+        graph.add_nodes(6);
+        assert_eq!(8, graph.count());
+        graph.reserve(100);
+        assert!(graph.capacity() >= 108);
+        graph.shrink_to_fit();
+        assert!(graph.capacity() >= 8);
+        assert_eq!(8, graph.component_count());
+    }
+
+    #[test]
+    fn should_use_narrow_index() {
+        let mut graph = Graph::<u8>::with_nodes(127);
+        assert!((1..127).all(|id| graph.connect(id - 1, id).is_some()));
+        assert_eq!(1, graph.component_count());
+        assert_eq!(127, graph.component_size(0));
+        assert!(graph.connected(0, 126));
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_on_index_overflow() {
+        let mut graph = Graph::<u8>::with_nodes(Graph::<u8>::MAX_NODES);
+        graph.add_node();
+    }
+
+    #[test]
+    fn should_link_by_rank() {
+        let mut graph = Graph::with_linking(8, Linking::Rank);
+        // a chain of singletons always stays of rank 1 below the first root
+        let root = graph.connect(0, 1).unwrap();
+        for id in 2..5 {
+            assert_eq!(Some(root), graph.connect(id, 0));
+        }
+        assert_eq!(1, graph.ranks[root]);
+        graph.connect(5, 6);
+        graph.connect(6, 7);
+        graph.connect(7, 0);
+        assert_eq!(1, graph.component_count());
+        assert_eq!(8, graph.component_size(5));
+        let root = graph.find(0);
+        assert_eq!(2, graph.ranks[root]);
+        graph.reset_to(3);
+        assert_eq!(vec![0; 3], graph.ranks);
+    }
+
+    #[test]
+    fn should_compress_paths() {
+        let strategies = [
+            Compression::Full,
+            Compression::Halving,
+            Compression::Splitting,
+            Compression::None
+        ];
+        for &compression in &strategies {
+            let mut graph = Graph::with_compression(6, compression);
+            // build the path 5 -> 4 -> 3 -> 2 -> 1 -> 0 by hand
+            for id in 1..6 {
+                graph.nodes[id] = id - 1;
+            }
+            graph.nodes[0] = Graph::<usize>::root_entry(6);
+            graph.components = 1;
+            assert_eq!(0, graph.find(5));
+            let expected = match compression {
+                Compression::Full => vec![0, 0, 0, 0, 0],
+                Compression::Halving => vec![0, 1, 1, 3, 3],
+                Compression::Splitting => vec![0, 0, 1, 2, 3],
+                Compression::None => vec![0, 1, 2, 3, 4],
+            };
+            assert_eq!(expected, graph.nodes[1..].to_vec(), "{:?}", compression);
+            assert!((0..6).all(|id| graph.find(id) == 0));
+            assert_eq!(6, graph.component_size(3));
+        }
+    }
+
+    #[test]
+    fn should_connect_node_ids() {
+        let mut graph = Graph::new(3);
+        let ids: Vec<NodeId> = graph.node_ids().collect();
+        assert_eq!(vec![0, 1, 2], ids.iter().map(|id| id.index()).collect::<Vec<_>>());
+        let root = graph.connect_nodes(ids[0], ids[2]).unwrap();
+        assert_eq!(root, graph.find_node(ids[2]));
+        assert!(graph.connected_nodes(ids[2], ids[0]));
+        assert_eq!(None, graph.connect_nodes(ids[0], ids[2]));
+        // handles stay valid in clones
+        let mut clone = graph.clone();
+        assert!(clone.connected_nodes(ids[0], ids[2]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_reject_foreign_node_ids() {
+        let mut graph = Graph::new(3);
+        let other = Graph::new(3);
+        let a = graph.node(0).unwrap();
+        graph.connect_nodes(a, other.node(1).unwrap());
+    }
+
+    #[test]
+    fn should_connect_unchecked() {
+        let mut graph = Graph::with_linking(4, Linking::Rank);
+        unsafe {
+            assert!(graph.connect_unchecked(0, 1).is_some());
+            assert!(graph.connect_unchecked(3, 2).is_some());
+            assert!(graph.connect_unchecked(1, 2).is_some());
+            assert!(graph.connect_unchecked(0, 3).is_none());
+            assert!(graph.connected_unchecked(1, 3));
+            assert_eq!(graph.find_unchecked(0), graph.find_unchecked(2));
+        }
+        assert_eq!(4, graph.component_size(3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_out_of_bounds() {
+        let mut graph = Graph::new(3);
+        graph.connected(0, 3);
+    }
+
+    #[test]
+    fn should_query_without_compressing() {
+        let mut graph = Graph::new(4);
+        for id in 1..4 {
+            graph.nodes[id] = id - 1;
+        }
+        graph.nodes[0] = Graph::<usize>::root_entry(4);
+        graph.components = 1;
+        assert_eq!(0, graph.find_ref(3));
+        assert!(graph.connected_ref(3, 1));
+        assert_eq!(vec![0, 1, 2], graph.nodes[1..].to_vec());
+    }
+
+    #[test]
+    fn should_flatten() {
+        let mut graph = Graph::with_compression(5, Compression::None);
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.connect(3, 4);
+        graph.connect(4, 1);
+        graph.flatten();
+        let root = graph.find(0);
+        assert!((0..5).all(|id| id == root || graph.nodes[id] == root));
+        assert_eq!(5, graph.component_size(2));
+        // a long chain is flattened in linear time
+        let n = 1 << 18;
+        let mut graph = Graph::from_parent_vec((1..n).chain(Some(n - 1)).collect()).unwrap();
+        graph.flatten();
+        assert!((0..n).all(|id| graph.nodes[id] == n - 1 || id == n - 1));
+        assert_eq!(n, graph.component_size(0));
+    }
+
+    #[test]
+    fn should_convert_raw_parts() {
+        let mut graph = Graph::<u16>::with_nodes(4);
+        graph.connect(0, 1);
+        graph.connect(2, 1);
+        let (nodes, ranks) = graph.into_raw_parts();
+        let mut graph = Graph::try_from_raw_parts(
+            nodes.clone(),
+            ranks.clone(),
+            Linking::Size,
+            Compression::Halving
+        ).unwrap();
+        assert_eq!(2, graph.component_count());
+        assert_eq!(3, graph.component_size(2));
+        let mut same = unsafe {
+            Graph::from_raw_parts(nodes.clone(), ranks, Linking::Size, Compression::Halving)
+        };
+        assert_eq!(2, same.component_count());
+        assert!(same.connected(0, 2));
+        // a root storing the wrong size is rejected
+        let mut invalid = nodes;
+        invalid[3] = Graph::<u16>::root_entry(2);
+        let result = Graph::try_from_raw_parts(invalid, vec![], Linking::Size, Compression::Halving);
+        assert_eq!(WufError::SizeMismatch { id: 3, expected: 1, found: 2 }, result.unwrap_err());
+    }
+
+    #[test]
+    fn should_build_from_parent_vec() {
+        let mut graph = Graph::from_parent_vec(vec![2, 2, 2, 4, 4, 1]).unwrap();
+        assert_eq!(2, graph.component_count());
+        assert_eq!(4, graph.component_size(5));
+        assert_eq!(6 + 1, graph.connected_pairs());
+        assert!(graph.connected(0, 5));
+        assert!(!graph.connected(0, 3));
+        assert_eq!(graph.connect(0, 3), Some(2));
+        assert_eq!(15, graph.connected_pairs());
+        assert_eq!(Ok(0), Graph::from_parent_vec(vec![]).map(|graph| graph.count()));
+        assert_eq!(
+            Err(WufError::InvalidParent { id: 1, parent: 9 }),
+            Graph::from_parent_vec(vec![0, 9]).map(|graph| graph.count())
+        );
+        assert_eq!(
+            Err(WufError::Cycle { id: 2 }),
+            Graph::from_parent_vec(vec![0, 2, 3, 2]).map(|graph| graph.count())
+        );
+    }
+
+    #[test]
+    fn should_debug_components() {
+        let mut graph = Graph::new(8);
+        graph.connect(7, 3);
+        graph.connect(0, 3);
+        graph.connect(2, 1);
+        graph.connect(5, 6);
+        assert_eq!("{{0, 3, 7}, {1, 2}, {4}, {5, 6}}", format!("{:?}", graph));
+        assert_eq!("{}", format!("{:?}", Graph::new(0)));
+    }
+
+    #[test]
+    fn should_export_json_components() {
+        let mut graph = Graph::new(5);
+        graph.connect(4, 0);
+        graph.connect(2, 3);
+        graph.connect(3, 4);
+        assert_eq!("[[0,2,3,4],[1]]", graph.to_json_components());
+        assert_eq!("[]", Graph::new(0).to_json_components());
+    }
+
+    #[test]
+    fn should_find_root() {
+        let mut graph = Graph::new(10);
+        assert_eq!(3, graph.find(3));
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        graph.connect(1, 3);
+        let root = graph.find(0);
+        assert!((0..4).all(|id| graph.find(id) == root));
+        assert_eq!(4, graph.find(4));
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn should_allocate_in_allocator() {
+        use std::alloc::{AllocError, Layout};
+        use std::cell::Cell;
+        use std::ptr::NonNull;
+
+        /// Allocator counting the bytes it allocates.
+        #[derive(Clone)]
+        struct Counting<'a>(&'a Cell<usize>);
+
+        unsafe impl<'a> Allocator for Counting<'a> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + layout.size());
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let allocated = Cell::new(0);
+        let mut graph = Graph::<u32, _>::with_nodes_in(8, Counting(&allocated));
+        graph.connect_all(vec![(0, 7), (7, 3)]);
+        assert!(graph.connected(3, 0));
+        assert_eq!(vec![0, 1, 2, 0, 3, 4, 5, 0], graph.labels());
+        assert_eq!(8 * 4, allocated.get());
+        let mut clone = graph.clone();
+        clone.add_nodes(1);
+        assert!(allocated.get() > 8 * 4);
+    }
+
+    #[test]
+    fn should_isolate_and_recycle_nodes() {
+        for &linking in &[Linking::Size, Linking::Rank] {
+            let mut graph = Graph::<u16>::with_config(6, 6, linking, Compression::Full);
+            // isolate with and without the circular lists of members
+            if linking == Linking::Rank {
+                graph.track_members();
+            }
+            graph.connect_all(vec![(0, 1), (2, 3), (1, 3), (4, 3)]);
+            assert!(graph.isolate(2));
+            assert!(!graph.isolate(2));
+            assert_eq!(3, graph.component_count());
+            assert_eq!(6, graph.connected_pairs());
+            assert_eq!(vec![0, 0, 1, 0, 0, 2], graph.labels());
+            assert_eq!(4, graph.component_size(4));
+            graph.remove_node(0);
+            graph.remove_node(5);
+            assert_eq!(2, graph.retired_count());
+            assert_eq!(2, graph.component_count());
+            assert_eq!(vec![usize::MAX, 0, 1, 0, 0, usize::MAX], graph.labels());
+            assert_eq!(vec![(1, 1), (3, 1)], graph.size_histogram().into_iter().collect::<Vec<_>>());
+            assert_eq!(Err(WufError::Retired { id: 0 }), graph.try_connect(1, 0));
+            assert_eq!(None, graph.node(5));
+            assert_eq!(4, graph.node_ids().count());
+            assert_eq!(5, graph.add_node().index());
+            assert_eq!(0, graph.add_node().index());
+            assert_eq!(6, graph.add_node().index());
+            assert_eq!(vec![0, 1, 2, 1, 1, 3, 4], graph.labels());
+            assert_eq!(3, graph.connected_pairs());
+            // a reused node can be connected, then retired again
+            graph.connect(0, 2);
+            graph.connect(5, 2);
+            graph.remove_node(0);
+            assert_eq!(vec![usize::MAX, 0, 1, 0, 0, 1, 2], graph.labels());
+            assert_eq!(3, graph.component_count());
+            assert_eq!(4, graph.connected_pairs());
+            assert_eq!(2, graph.component_size(5));
+            assert_eq!(0, graph.add_node().index());
+            assert_eq!(4, graph.component_count());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "node 3 has been removed")]
+    fn should_panic_on_retired_node() {
+        let mut graph = Graph::new(5);
+        graph.remove_node(3);
+        graph.connect_all(vec![(0, 1), (3, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "node 3 has been removed")]
+    fn should_panic_on_retiring_twice() {
+        let mut graph = Graph::new(5);
+        graph.remove_node(3);
+        graph.remove_node(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "has been removed")]
+    fn should_reject_stale_node_ids() {
+        let mut graph = Graph::new(5);
+        let stale = graph.node(3).unwrap();
+        graph.remove_node(3);
+        let id = graph.add_node();
+        assert_eq!(stale.index(), id.index());
+        assert_eq!(id, graph.find_node(id));
+        graph.find_node(stale);
+    }
+
+    #[test]
+    fn should_move_to() {
+        let mut graph = Graph::new(5);
+        graph.connect_all(vec![(0, 1), (1, 2), (3, 4)]);
+        assert!(graph.move_to(2, 4));
+        assert!(!graph.move_to(2, 3));
+        assert_eq!(vec![0, 0, 1, 1, 1], graph.labels());
+        assert_eq!(4, graph.connected_pairs());
+        assert!(!graph.move_to(0, 1));
+        assert!(!graph.move_to(0, 0));
+        assert_eq!(2, graph.component_count());
+    }
+}
@@ -0,0 +1,254 @@
+//! Connected-component labeling of grids of cells, and of volumes of voxels.
+//! 
+//! Cells are given in row-major order (the cell at column `x` and row `y` is
+//! `y * width + x`), voxels slice by slice (the voxel at column `x`, row `y`
+//! and slice `z` is `(z * height + y) * width + x`), and labels are compact:
+//! background cells are labeled 0, while the components of foreground cells
+//! are labeled from 1, in order of their first cell.
+
+use graph::Graph;
+
+/// Cells considered adjacent to a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Cells sharing an edge in 2D (4 neighbors), or a face in 3D (6
+    /// neighbors).
+    #[default]
+    Orthogonal,
+    /// Cells sharing an edge or a corner in 2D (8 neighbors), or a face, an
+    /// edge or a corner in 3D (26 neighbors).
+    Diagonal
+}
+
+/// Returns the label of every cell of the given grid, where orthogonally
+/// adjacent foreground (`true`) cells belong to the same component.
+/// 
+/// Panics if the number of cells is not `width * height`.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the grid.
+/// * `height` Number of rows of the grid.
+/// * `cells` Whether each cell belongs to the foreground.
+/// 
+/// # Example
+/// ```
+/// let cells = [
+///     true, false, true,
+///     true, false, false,
+///     false, true, true
+/// ];
+/// assert_eq!(vec![1, 0, 2, 1, 0, 0, 0, 3, 3], wuf::grid::label_grid(3, 3, &cells));
+/// ```
+pub fn label_grid(width: usize, height: usize, cells: &[bool]) -> Vec<usize> {
+    label_grid_with(width, height, cells, Connectivity::Orthogonal)
+}
+
+/// Returns the label of every cell of the given grid, where foreground
+/// (`true`) cells adjacent according to the given connectivity belong to the
+/// same component.
+/// 
+/// Panics if the number of cells is not `width * height`.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the grid.
+/// * `height` Number of rows of the grid.
+/// * `cells` Whether each cell belongs to the foreground.
+/// * `connectivity` Cells considered adjacent to a cell.
+/// 
+/// # Example
+/// ```
+/// use wuf::grid::{label_grid_with, Connectivity};
+/// 
+/// let cells = [true, false, false, true];
+/// assert_eq!(vec![1, 0, 0, 1], label_grid_with(2, 2, &cells, Connectivity::Diagonal));
+/// ```
+pub fn label_grid_with(
+    width: usize,
+    height: usize,
+    cells: &[bool],
+    connectivity: Connectivity
+) -> Vec<usize> {
+    assert_eq!(
+        width.checked_mul(height), Some(cells.len()),
+        "expected {} by {} cells", width, height
+    );
+    let mut graph = Graph::new(cells.len());
+    for y in 0..height {
+        for x in (0..width).filter(|&x| cells[y * width + x]) {
+            let cell = y * width + x;
+            // connect to the neighbors already visited (left and above)
+            let mut neighbors = [None; 4];
+            if x > 0 {
+                neighbors[0] = Some(cell - 1);
+            }
+            if y > 0 {
+                neighbors[1] = Some(cell - width);
+                if connectivity == Connectivity::Diagonal {
+                    neighbors[2] = if x > 0 { Some(cell - width - 1) } else { None };
+                    neighbors[3] = if x + 1 < width { Some(cell - width + 1) } else { None };
+                }
+            }
+            for neighbor in neighbors.iter().flatten().cloned() {
+                if cells[neighbor] {
+                    graph.connect(cell, neighbor);
+                }
+            }
+        }
+    }
+    labels(&mut graph, cells)
+}
+
+/// Returns the label of every voxel of the given volume, where foreground
+/// (`true`) voxels sharing a face belong to the same component.
+/// 
+/// Panics if the number of voxels is not `width * height * depth`.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the volume.
+/// * `height` Number of rows of the volume.
+/// * `depth` Number of slices of the volume.
+/// * `voxels` Whether each voxel belongs to the foreground.
+/// 
+/// # Example
+/// ```
+/// // two slices of 2 by 2 voxels
+/// let voxels = [true, false, false, false, true, false, false, true];
+/// assert_eq!(vec![1, 0, 0, 0, 1, 0, 0, 2], wuf::grid::label_volume(2, 2, 2, &voxels));
+/// ```
+pub fn label_volume(width: usize, height: usize, depth: usize, voxels: &[bool]) -> Vec<usize> {
+    label_volume_with(width, height, depth, voxels, Connectivity::Orthogonal)
+}
+
+/// Returns the label of every voxel of the given volume, where foreground
+/// (`true`) voxels adjacent according to the given connectivity belong to the
+/// same component.
+/// 
+/// Panics if the number of voxels is not `width * height * depth`.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the volume.
+/// * `height` Number of rows of the volume.
+/// * `depth` Number of slices of the volume.
+/// * `voxels` Whether each voxel belongs to the foreground.
+/// * `connectivity` Voxels considered adjacent to a voxel.
+/// 
+/// # Example
+/// ```
+/// use wuf::grid::{label_volume_with, Connectivity};
+/// 
+/// let voxels = [true, false, false, false, false, false, false, true];
+/// let labels = label_volume_with(2, 2, 2, &voxels, Connectivity::Diagonal);
+/// assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 1], labels);
+/// ```
+pub fn label_volume_with(
+    width: usize,
+    height: usize,
+    depth: usize,
+    voxels: &[bool],
+    connectivity: Connectivity
+) -> Vec<usize> {
+    assert_eq!(
+        width.checked_mul(height).and_then(|area| area.checked_mul(depth)), Some(voxels.len()),
+        "expected {} by {} by {} voxels", width, height, depth
+    );
+    // offsets of the neighbors visited before a voxel, as (dx, dy, dz)
+    let offsets: Vec<(isize, isize, isize)> = match connectivity {
+        Connectivity::Orthogonal => vec![(-1, 0, 0), (0, -1, 0), (0, 0, -1)],
+        Connectivity::Diagonal => {
+            let mut offsets = Vec::with_capacity(13);
+            for dz in -1..=0 {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if (dz, dy, dx) < (0, 0, 0) {
+                            offsets.push((dx, dy, dz));
+                        }
+                    }
+                }
+            }
+            offsets
+        }
+    };
+    let mut graph = Graph::new(voxels.len());
+    let index = |x: usize, y: usize, z: usize| (z * height + y) * width + x;
+    for z in 0..depth {
+        for y in 0..height {
+            for x in (0..width).filter(|&x| voxels[index(x, y, z)]) {
+                for &(dx, dy, dz) in &offsets {
+                    let (nx, ny, nz) = (
+                        x.wrapping_add(dx as usize),
+                        y.wrapping_add(dy as usize),
+                        z.wrapping_add(dz as usize)
+                    );
+                    // wrapped coordinates are out of bounds as well
+                    if nx < width && ny < height && nz < depth && voxels[index(nx, ny, nz)] {
+                        graph.connect(index(x, y, z), index(nx, ny, nz));
+                    }
+                }
+            }
+        }
+    }
+    labels(&mut graph, voxels)
+}
+
+/// Returns the compact label of every cell, where the given graph connects
+/// the adjacent foreground cells.
+pub(crate) fn labels(graph: &mut Graph, cells: &[bool]) -> Vec<usize> {
+    let mut labels = vec![0; cells.len()];
+    let mut next = 1;
+    for cell in (0..cells.len()).filter(|&cell| cells[cell]) {
+        let root = graph.find(cell);
+        // the root is labeled when the first cell of its component is visited
+        if labels[root] == 0 {
+            labels[root] = next;
+            next += 1;
+        }
+        labels[cell] = labels[root];
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_label_grid() {
+        let cells: Vec<bool> = [
+            "##..#",
+            "..#.#",
+            ".#..#",
+            "#.###"
+        ].iter().flat_map(|row| row.chars().map(|c| c == '#')).collect();
+        assert_eq!(vec![
+            1, 1, 0, 0, 2,
+            0, 0, 3, 0, 2,
+            0, 4, 0, 0, 2,
+            5, 0, 2, 2, 2
+        ], label_grid(5, 4, &cells));
+        // diagonals join every component through the cell in the third row
+        assert_eq!(vec![
+            1, 1, 0, 0, 1,
+            0, 0, 1, 0, 1,
+            0, 1, 0, 0, 1,
+            1, 0, 1, 1, 1
+        ], label_grid_with(5, 4, &cells, Connectivity::Diagonal));
+        assert!(label_grid(0, 3, &[]).is_empty());
+    }
+
+    #[test]
+    fn should_label_volume() {
+        // a 3 by 3 by 3 cube with a voxel in each corner and one in the center
+        let mut voxels = vec![false; 27];
+        for &voxel in &[0, 2, 6, 8, 13, 18, 20, 24, 26] {
+            voxels[voxel] = true;
+        }
+        let labels = label_volume(3, 3, 3, &voxels);
+        assert_eq!(9, labels.iter().cloned().max().unwrap());
+        let labels = label_volume_with(3, 3, 3, &voxels, Connectivity::Diagonal);
+        assert!(voxels.iter().zip(&labels).all(|(&voxel, &label)| label == voxel as usize));
+        // volumes made of a single slice are labeled as grids
+        let cells = [true, true, false, false, false, true];
+        assert_eq!(label_grid(3, 2, &cells), label_volume(3, 2, 1, &cells));
+        assert_eq!(vec![1, 1, 0, 0, 0, 2], label_volume(3, 2, 1, &cells));
+    }
+}
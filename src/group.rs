@@ -0,0 +1,270 @@
+use error::WufError;
+
+/// Group whose elements relate the nodes of a `GroupGraph`.
+/// 
+/// The composition must be associative, with `identity()` as neutral
+/// element, and with `inverse()` such that `a.compose(&a.inverse())` is the
+/// identity, while it need not be commutative (as for permutations).
+pub trait Group: Clone + PartialEq {
+    /// Returns the neutral element of the group.
+    fn identity() -> Self;
+
+    /// Returns the composition of `self` followed by `other`.
+    /// 
+    /// # Arguments
+    /// * `other` The element to compose after `self`.
+    fn compose(&self, other: &Self) -> Self;
+
+    /// Returns the inverse of `self`.
+    fn inverse(&self) -> Self;
+}
+
+macro_rules! impl_additive_group {
+    ($($t:ty),*) => {
+        $(
+            /// Integers under (wrapping) addition.
+            impl Group for $t {
+                #[inline]
+                fn identity() -> Self {
+                    0
+                }
+
+                #[inline]
+                fn compose(&self, other: &Self) -> Self {
+                    self.wrapping_add(*other)
+                }
+
+                #[inline]
+                fn inverse(&self) -> Self {
+                    self.wrapping_neg()
+                }
+            }
+        )*
+    };
+}
+
+impl_additive_group!(i8, i16, i32, i64, i128, isize);
+
+/// Booleans under exclusive or, that is parities.
+impl Group for bool {
+    #[inline]
+    fn identity() -> Self {
+        false
+    }
+
+    #[inline]
+    fn compose(&self, other: &Self) -> Self {
+        self ^ other
+    }
+
+    #[inline]
+    fn inverse(&self) -> Self {
+        *self
+    }
+}
+
+/// Union-find where every node has a label in a group, known relative to the
+/// other nodes of its component, so that the relation between two nodes can
+/// be asserted and inferred, and inconsistent assertions are reported as
+/// contradictions.
+/// 
+/// The relation from `a` to `b` is the element `g` such that the label of `b`
+/// is the label of `a` composed with `g`. This generalizes `ParityGraph`
+/// (with `bool`) and `WeightedGraph` (with `i64`) to any group, such as xor
+/// masks, multiplicative ratios, or permutations.
+/// 
+/// # Example
+/// ```
+/// use wuf::{Group, GroupGraph};
+/// 
+/// // bit flips between registers, as xor masks
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Mask(u8);
+/// 
+/// impl Group for Mask {
+///     fn identity() -> Self { Mask(0) }
+///     fn compose(&self, other: &Self) -> Self { Mask(self.0 ^ other.0) }
+///     fn inverse(&self) -> Self { self.clone() }
+/// }
+/// 
+/// let mut graph = GroupGraph::new(3);
+/// graph.connect(0, 1, Mask(0b0011)).unwrap();
+/// graph.connect(1, 2, Mask(0b0110)).unwrap();
+/// assert_eq!(Some(Mask(0b0101)), graph.relation(0, 2));
+/// assert!(graph.connect(2, 0, Mask(0b1111)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupGraph<G> {
+    parents: Vec<usize>,    // parent of each node, roots are their own parent
+    labels: Vec<G>,         // relation from the parent to each node
+    sizes: Vec<usize>,      // size of the tree of each root
+    components: usize       // number of disjoint components
+}
+
+impl<G: Group> GroupGraph<G> {
+
+    /// Returns a new GroupGraph with the given number of nodes, without
+    /// relations.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::GroupGraph::<i64>::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> GroupGraph<G> {
+        GroupGraph {
+            parents: (0..n).collect(),
+            labels: vec![G::identity(); n],
+            sizes: vec![1; n],
+            components: n
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the root of the given node, and the relation from the root to
+    /// the node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> (usize, G) {
+        let mut root = id;
+        let mut label = G::identity();
+        while self.parents[root] != root {
+            // relations compose from the root down to the node
+            label = self.labels[root].compose(&label);
+            root = self.parents[root];
+        }
+        // make every node in path point to the root, with its own relation
+        let mut node = id;
+        let mut node_label = label.clone();
+        while self.parents[node] != root && node != root {
+            let parent = self.parents[node];
+            let parent_label = node_label.compose(&self.labels[node].inverse());
+            self.parents[node] = root;
+            self.labels[node] = node_label;
+            node = parent;
+            node_label = parent_label;
+        }
+        (root, label)
+    }
+
+    /// Returns the relation from `a` to `b` implied by the relations asserted
+    /// so far, or `None` if the nodes are not related.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn relation(&mut self, a: usize, b: usize) -> Option<G> {
+        let (a_root, a_label) = self.find(a);
+        let (b_root, b_label) = self.find(b);
+        if a_root == b_root {
+            Some(a_label.inverse().compose(&b_label))
+        } else {
+            None
+        }
+    }
+
+    /// Asserts that the relation from `a` to `b` is `g`, that is the label of
+    /// `b` is the label of `a` composed with `g`.
+    /// 
+    /// Returns true only if the two nodes belonged to different components,
+    /// or an error if the relation contradicts the relations asserted so far
+    /// (in which case the graph is not modified).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// * `g` Relation from the first node to the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::GroupGraph::new(10);
+    /// assert_eq!(Ok(true), graph.connect(0, 1, 4i64));
+    /// assert_eq!(Ok(false), graph.connect(1, 0, -4));
+    /// assert!(graph.connect(0, 1, 5).is_err());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize, g: G) -> Result<bool, WufError> {
+        let (a_root, a_label) = self.find(a);
+        let (b_root, b_label) = self.find(b);
+        if a_root == b_root {
+            return if a_label.inverse().compose(&b_label) == g {
+                Ok(false)
+            } else {
+                Err(WufError::Contradiction { a, b })
+            };
+        }
+        // relation from the root of a to the root of b
+        let roots_label = a_label.compose(&g).compose(&b_label.inverse());
+        // link the smaller tree below the larger one
+        if self.sizes[a_root] < self.sizes[b_root] {
+            self.parents[a_root] = b_root;
+            self.labels[a_root] = roots_label.inverse();
+            self.sizes[b_root] += self.sizes[a_root];
+        } else {
+            self.parents[b_root] = a_root;
+            self.labels[b_root] = roots_label;
+            self.sizes[a_root] += self.sizes[b_root];
+        }
+        self.components -= 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Permutation of 3 elements, where `compose` applies `self` first.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Perm([usize; 3]);
+
+    impl Group for Perm {
+        fn identity() -> Self {
+            Perm([0, 1, 2])
+        }
+
+        fn compose(&self, other: &Self) -> Self {
+            Perm([other.0[self.0[0]], other.0[self.0[1]], other.0[self.0[2]]])
+        }
+
+        fn inverse(&self) -> Self {
+            let mut inverse = [0; 3];
+            for (i, &j) in self.0.iter().enumerate() {
+                inverse[j] = i;
+            }
+            Perm(inverse)
+        }
+    }
+
+    #[test]
+    fn should_relate_non_commutative_labels() {
+        let perms = [[0, 1, 2], [1, 0, 2], [0, 2, 1], [2, 0, 1], [1, 2, 0], [2, 1, 0]];
+        // labels to recover, asserted through a chain of relations
+        let labels: Vec<Perm> = (0..12).map(|i| Perm(perms[(i * 7 + 3) % 6])).collect();
+        let relation = |a: usize, b: usize| labels[a].inverse().compose(&labels[b]);
+        let mut graph = GroupGraph::new(labels.len());
+        // a path visiting the nodes out of order
+        let path: Vec<usize> = (0..labels.len()).map(|i| i * 5 % labels.len()).collect();
+        for pair in path.windows(2) {
+            assert_eq!(Ok(true), graph.connect(pair[0], pair[1], relation(pair[0], pair[1])));
+        }
+        for a in 0..labels.len() {
+            for b in 0..labels.len() {
+                assert_eq!(Some(relation(a, b)), graph.relation(a, b));
+            }
+        }
+        assert!(graph.connect(0, 4, relation(4, 0).compose(&Perm([1, 0, 2]))).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Archived};
+
+/// Unsigned integer type used to store node IDs inside a graph.
+/// 
+/// Narrower types reduce the memory footprint (and memory traffic) of large
+/// graphs, at the cost of limiting the number of nodes to `Index::MAX`.
+pub trait Index: Copy + Eq + Ord + Hash + Debug + Default + Send + Sync + 'static {
+    /// The largest value representable by the type (capped to `usize::MAX`).
+    const MAX: usize;
+
+    /// Converts the given `usize` into the index type.
+    /// 
+    /// # Arguments
+    /// * `value` The value to convert, that must not be greater than `MAX`.
+    fn from_usize(value: usize) -> Self;
+
+    /// Converts the index into a `usize`.
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_index {
+    ($($t:ty),*) => {
+        $(
+            impl Index for $t {
+                const MAX: usize = if (<$t>::MAX as u128) < (usize::MAX as u128) {
+                    <$t>::MAX as usize
+                } else {
+                    usize::MAX
+                };
+
+                #[inline]
+                fn from_usize(value: usize) -> Self {
+                    debug_assert!(value <= <Self as Index>::MAX);
+                    value as $t
+                }
+
+                #[inline]
+                fn index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(u8, u16, u32, u64, usize);
+
+/// Index type whose archived form (see the `rkyv` feature) preserves every
+/// value, which excludes `usize` since it is archived with 32 bits.
+#[cfg(feature = "rkyv")]
+pub trait ArchiveIndex: Index + Archive {
+    /// Converts the given archived index into a `usize`.
+    fn archived_index(archived: &Archived<Self>) -> usize;
+}
+
+#[cfg(feature = "rkyv")]
+macro_rules! impl_archive_index {
+    ($($t:ty),*) => {
+        $(
+            impl ArchiveIndex for $t {
+                #[inline]
+                fn archived_index(archived: &Archived<Self>) -> usize {
+                    archived.to_native() as usize
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "rkyv")]
+impl_archive_index!(u16, u32, u64);
+
+#[cfg(feature = "rkyv")]
+impl ArchiveIndex for u8 {
+    #[inline]
+    fn archived_index(archived: &u8) -> usize {
+        *archived as usize
+    }
+}
@@ -0,0 +1,203 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use graph::Graph;
+
+/// Weighted union-find with path compression over arbitrary hashable keys.
+/// 
+/// Keys are interned the first time they are connected (or inserted), and
+/// mapped to the nodes of a dense `Graph`.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::KeyedGraph::new();
+/// graph.connect("alice@x.com", "alice@y.com");
+/// graph.connect("bob@x.com", "bob@z.com");
+/// assert!(graph.connected("alice@y.com", "alice@x.com"));
+/// assert!(!graph.connected("alice@x.com", "bob@x.com"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyedGraph<K: Hash + Eq> {
+    graph: Graph,           // dense graph of the interned keys
+    ids: HashMap<K, usize>, // ID of each interned key
+    keys: Vec<K>            // interned key of each ID
+}
+
+impl<K: Hash + Eq + Clone> KeyedGraph<K> {
+
+    /// Returns a new empty KeyedGraph.
+    pub fn new() -> KeyedGraph<K> {
+        KeyedGraph::with_capacity(0)
+    }
+
+    /// Returns a new empty KeyedGraph, able to hold at least `capacity` keys
+    /// without reallocating.
+    /// 
+    /// # Arguments
+    /// * `capacity` Number of keys the graph can hold without reallocating.
+    pub fn with_capacity(capacity: usize) -> KeyedGraph<K> {
+        KeyedGraph {
+            graph: Graph::with_capacity(0, capacity),
+            ids: HashMap::with_capacity(capacity),
+            keys: Vec::with_capacity(capacity)
+        }
+    }
+
+    /// Returns the number of interned keys.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::KeyedGraph::new();
+    /// graph.connect('a', 'b');
+    /// assert_eq!(2, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the number of disjoint components among the interned keys, in
+    /// constant time.
+    pub fn component_count(&self) -> usize {
+        self.graph.component_count()
+    }
+
+    /// Interns the given key, if it was never seen before, and returns the ID
+    /// of its node.
+    /// 
+    /// # Arguments
+    /// * `key` The key to intern.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::KeyedGraph::new();
+    /// assert_eq!(0, graph.insert("a"));
+    /// assert_eq!(1, graph.insert("b"));
+    /// assert_eq!(0, graph.insert("a"));
+    /// ```
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let id = self.graph.add_node().index();
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        id
+    }
+
+    /// Returns the ID of the node of the given key, if interned.
+    /// 
+    /// # Arguments
+    /// * `key` The key to look up.
+    pub fn id<Q>(&self, key: &Q) -> Option<usize>
+        where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        self.ids.get(key).cloned()
+    }
+
+    /// Returns the key of the given node ID.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn key(&self, id: usize) -> &K {
+        &self.keys[id]
+    }
+
+    /// Returns the dense graph of the interned keys.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Returns the number of keys in the component the given key belongs to,
+    /// which is 1 if the key was never interned.
+    /// 
+    /// # Arguments
+    /// * `key` The key to look up.
+    pub fn component_size<Q>(&mut self, key: &Q) -> usize
+        where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        match self.id(key) {
+            Some(id) => self.graph.component_size(id),
+            None => 1
+        }
+    }
+
+    /// Returns the representative key of the component the given key belongs
+    /// to, or `None` if the key was never interned.
+    /// 
+    /// # Arguments
+    /// * `key` The key to look up.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::KeyedGraph::new();
+    /// graph.connect("a", "b");
+    /// assert_eq!(graph.find("a").cloned(), graph.find("b").cloned());
+    /// assert_eq!(None, graph.find("c"));
+    /// ```
+    pub fn find<Q>(&mut self, key: &Q) -> Option<&K>
+        where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        let id = self.id(key)?;
+        let root = self.graph.find(id);
+        Some(&self.keys[root])
+    }
+
+    /// Returns true only if the two given keys are connected, otherwise
+    /// returns false. Every key is connected to itself, even if never
+    /// interned.
+    /// 
+    /// # Arguments
+    /// * `a` The first key.
+    /// * `b` The second key.
+    pub fn connected<Q>(&mut self, a: &Q, b: &Q) -> bool
+        where K: Borrow<Q>, Q: Hash + Eq + ?Sized
+    {
+        match (self.id(a), self.id(b)) {
+            (Some(a), Some(b)) => self.graph.connected(a, b),
+            _ => a == b
+        }
+    }
+
+    /// Connects the two given keys, interning them if never seen before.
+    /// 
+    /// Returns true only if the two keys belonged to different components.
+    /// 
+    /// # Arguments
+    /// * `a` The first key.
+    /// * `b` The second key.
+    pub fn connect(&mut self, a: K, b: K) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        self.graph.connect(a, b).is_some()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedGraph<K> {
+    fn default() -> KeyedGraph<K> {
+        KeyedGraph::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect_keys() {
+        let mut graph = KeyedGraph::new();
+        assert!(graph.connect(String::from("a"), String::from("b")));
+        assert!(graph.connect(String::from("c"), String::from("b")));
+        assert!(!graph.connect(String::from("a"), String::from("c")));
+        graph.insert(String::from("d"));
+        assert_eq!(4, graph.count());
+        assert_eq!(2, graph.component_count());
+        assert!(graph.connected("a", "c"));
+        assert!(!graph.connected("a", "d"));
+        assert!(graph.connected("e", "e"));
+        assert!(!graph.connected("a", "e"));
+        assert_eq!(3, graph.component_size("b"));
+        assert_eq!(Some(3), graph.id("d"));
+        assert_eq!("d", graph.key(3));
+    }
+}
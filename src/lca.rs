@@ -0,0 +1,134 @@
+//! Lowest common ancestors of rooted trees.
+//! 
+//! Trees are given as the parent of each node, where roots are their own
+//! parent (as accepted by `Graph::from_parent_vec`), so that a single array
+//! can describe a forest.
+
+use error::WufError;
+use graph::Graph;
+
+/// Returns the lowest common ancestor of the two nodes of every query, or
+/// `None` if they belong to different trees, using Tarjan's offline
+/// algorithm in near-linear time.
+/// 
+/// Every tree is visited depth-first (without recursion), and when a node is
+/// done its component is merged into the component of its parent, whose
+/// ancestor is the parent: the common ancestor of a node being visited and a
+/// node already done is then the ancestor of the component of the latter.
+/// 
+/// Returns an error if a parent or a query refers to a node out of bounds, or
+/// if a node belongs to a cycle of parents.
+/// 
+/// # Arguments
+/// * `parents` The parent of each node.
+/// * `queries` Pairs of nodes whose lowest common ancestor is requested.
+/// 
+/// # Example
+/// ```
+/// //     0      4
+/// //    / \
+/// //   1   2
+/// //       |
+/// //       3
+/// let parents = [0, 0, 0, 2, 4];
+/// let ancestors = wuf::lca::tarjan(&parents, &[(1, 3), (3, 2), (3, 3), (1, 4)]).unwrap();
+/// assert_eq!(vec![Some(0), Some(2), Some(3), None], ancestors);
+/// ```
+pub fn tarjan(parents: &[usize], queries: &[(usize, usize)]) -> Result<Vec<Option<usize>>, WufError> {
+    let n = parents.len();
+    // children of every node, as adjacency lists in a single array
+    let mut offsets = vec![0; n + 1];
+    for (id, &parent) in parents.iter().enumerate() {
+        if parent >= n {
+            return Err(WufError::InvalidParent { id, parent });
+        }
+        if parent != id {
+            offsets[parent + 1] += 1;
+        }
+    }
+    for id in 0..n {
+        offsets[id + 1] += offsets[id];
+    }
+    let mut children = vec![0; offsets[n]];
+    let mut next = offsets.clone();
+    for (id, &parent) in parents.iter().enumerate().filter(|&(id, &parent)| id != parent) {
+        children[next[parent]] = id;
+        next[parent] += 1;
+    }
+    // queries of every node, as adjacency lists of (other node, query)
+    let mut pending: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    for (query, &(a, b)) in queries.iter().enumerate() {
+        for &id in &[a, b] {
+            if id >= n {
+                return Err(WufError::OutOfBounds { id, count: n });
+            }
+        }
+        pending[a].push((b, query));
+        pending[b].push((a, query));
+    }
+
+    const UNVISITED: usize = usize::MAX;
+    let mut graph = Graph::new(n);
+    let mut ancestors: Vec<usize> = (0..n).collect();
+    // root of the tree of every visited node
+    let mut trees = vec![UNVISITED; n];
+    let mut done = vec![false; n];
+    let mut answers = vec![None; queries.len()];
+    let mut stack = Vec::new();
+    for root in (0..n).filter(|&id| parents[id] == id) {
+        trees[root] = root;
+        // every node on the stack is paired with the index of its next child
+        stack.push((root, offsets[root]));
+        while let Some(&mut (id, ref mut child)) = stack.last_mut() {
+            if *child < offsets[id + 1] {
+                let next = children[*child];
+                *child += 1;
+                trees[next] = root;
+                stack.push((next, offsets[next]));
+                continue;
+            }
+            stack.pop();
+            done[id] = true;
+            for &(other, query) in &pending[id] {
+                if done[other] && trees[other] == root {
+                    answers[query] = Some(ancestors[graph.find(other)]);
+                }
+            }
+            if id != root {
+                let parent = parents[id];
+                graph.connect(parent, id);
+                let component = graph.find(parent);
+                ancestors[component] = parent;
+            }
+        }
+    }
+    // nodes not reachable from any root belong to a cycle
+    match trees.iter().position(|&tree| tree == UNVISITED) {
+        Some(id) => Err(WufError::Cycle { id }),
+        None => Ok(answers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_find_lowest_common_ancestors() {
+        // a path of 10000 nodes, deep enough to overflow a recursive visit
+        let n: usize = 10_000;
+        let mut parents: Vec<usize> = (0..n).map(|id| id.saturating_sub(1)).collect();
+        parents.push(n);
+        let queries = [(n - 1, 5), (7, n - 2), (0, n), (n, n)];
+        let ancestors = tarjan(&parents, &queries).unwrap();
+        assert_eq!(vec![Some(5), Some(7), None, Some(n)], ancestors);
+    }
+
+    #[test]
+    fn should_reject_invalid_trees() {
+        assert_eq!(Err(WufError::InvalidParent { id: 1, parent: 5 }), tarjan(&[0, 5], &[]));
+        assert_eq!(Err(WufError::OutOfBounds { id: 2, count: 2 }), tarjan(&[0, 0], &[(0, 2)]));
+        assert_eq!(Err(WufError::Cycle { id: 1 }), tarjan(&[0, 2, 1], &[]));
+        assert_eq!(Ok(Vec::new()), tarjan(&[], &[]));
+    }
+}
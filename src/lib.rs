@@ -13,118 +13,165 @@
 //!     graph.connect(node_id1, node_id2);
 //! }
 //! ```
+//! 
+//! # Features
+//! The `std` feature (enabled by default) provides everything that depends
+//! on the standard library. Without it, the crate is `no_std` and only needs
+//! `alloc`, providing `Graph` (with `GraphBuilder`, `FrozenPartition` and the
+//! partition comparisons), `ArrayGraph`, `SmallGraph`, `StorageGraph` and the
+//! `UnionFind` trait, where `Graph` can still be serialized with the `serde`
+//! feature. The `allocator_api` feature (nightly only) lets the arrays of a
+//! `Graph`, and the storage of a `StorageGraph`, live in any allocator. The
+//! `parallel` feature adds the methods of `Graph` that split their work among
+//! threads (see `concurrent`).
 
-pub struct Graph {
-    nodes: Vec<usize>,  // list of nodes' ids.
-    sizes: Vec<usize>   // number of nodes in the tree which root is sizes[i]
-}
-
-impl Graph {
-
-    /// Returns a new Graph with the given number of nodes.
-    /// 
-    /// # Arguments
-    /// * `n` Number of nodes belonging to the graph.
-    /// 
-    pub fn new(n: usize) -> Graph {
-        Graph {
-            nodes: (0..n).map(|x| x).collect(),
-            sizes: vec![0; n]
-        }
-    }
-
-    /// Returns the number of nodes.
-    /// 
-    /// # Example
-    /// ```
-    /// let graph = wuf::Graph::new(10);
-    /// println!("Number of nodes: {}", graph.count());
-    /// ```
-    pub fn count(&self) -> usize {
-        self.nodes.len()
-    }
-
-    /// Returns true only if the two given nodes are connected,
-    /// otherwise returns false.
-    /// 
-    /// # Arguments
-    /// * `a` ID of the first node.
-    /// * `b` ID of the second node.
-    /// 
-    /// # Example
-    /// ```
-    /// let mut graph = wuf::Graph::new(10);
-    /// let node1 = 0;
-    /// let node2 = 1;
-    /// println!("Are nodes connected? {}", graph.connected(node1, node2));
-    /// ```
-    pub fn connected(&mut self, a: usize, b: usize) -> bool {
-        // check if the two nodes have the same root
-        self.root(a) == self.root(b)
-    }
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 
-    /// Connects the two given nodes.
-    /// 
-    /// # Arguments
-    /// * `a` ID of the first node.
-    /// * `b` ID of the second node.
-    /// 
-    /// # Example
-    /// ```
-    /// let mut graph = wuf::Graph::new(10);
-    /// let node1 = 0;
-    /// let node2 = 1;
-    /// graph.connect(node1, node2);
-    /// ```
-    pub fn connect(&mut self, a: usize, b: usize) {
-        let a_root = self.root(a);
-        let b_root = self.root(b);
-        if a_root == b_root {
-            // already connected
-            return;
-        }
-        // balance by linking root of smaller tree to root of larger tree
-        if self.sizes[a_root] < self.sizes[b_root] {
-            self.nodes[a_root] = b_root;
-            self.sizes[b_root] += self.sizes[a_root];
-        } else {
-            self.nodes[b_root] = a_root;
-            self.sizes[a_root] += self.sizes[b_root];
-        }
-    }
+#[cfg(not(any(test, feature = "std")))]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "rkyv")]
+extern crate rkyv;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
 
-    /// Returns the root of the given node.
-    /// 
-    /// # Arguments
-    /// * `id` ID of the child node.
-    fn root(&mut self, id: usize) -> usize {
-        let mut root = id;
-        while root != self.nodes[id] {
-            // make every other node in path point to its grandparent
-            self.nodes[root] = self.nodes[self.nodes[root]];
-            root = self.nodes[id];
-        }
-        root
-    }
+/// Stand-in for the standard library when it is not available, so that the
+/// modules refer to the same paths with and without the `std` feature.
+#[cfg(not(any(test, feature = "std")))]
+mod std {
+    #[cfg(feature = "allocator_api")]
+    pub use alloc::alloc;
+    pub use alloc::{boxed, collections, string, vec};
+    pub use core::*;
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(feature = "std")]
+mod aggregate;
+mod array;
+#[cfg(feature = "std")]
+mod binary;
+mod builder;
+#[cfg(feature = "std")]
+pub mod cluster;
+#[cfg(feature = "std")]
+pub mod concurrent;
+#[cfg(feature = "std")]
+pub mod congruence;
+#[cfg(feature = "std")]
+mod dot;
+#[cfg(feature = "std")]
+pub mod dynamic;
+#[cfg(feature = "std")]
+mod edges;
+#[cfg(feature = "std")]
+pub mod equality;
+#[cfg(feature = "std")]
+pub mod external;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod frozen;
+mod graph;
+#[cfg(feature = "std")]
+pub mod grid;
+#[cfg(feature = "std")]
+mod group;
+mod index;
+#[cfg(feature = "std")]
+mod keyed;
+#[cfg(feature = "std")]
+pub mod lca;
+#[cfg(all(feature = "mmap", unix))]
+mod mapped;
+#[cfg(feature = "std")]
+pub mod maze;
+#[cfg(feature = "std")]
+pub mod mst;
+mod node;
+#[cfg(feature = "std")]
+mod packed;
+#[cfg(feature = "std")]
+mod parity;
+mod partition;
+#[cfg(feature = "std")]
+mod payload;
+#[cfg(feature = "std")]
+pub mod percolation;
+#[cfg(feature = "std")]
+mod persistent;
+#[cfg(feature = "std")]
+mod quick;
+#[cfg(feature = "std")]
+mod randomized;
+#[cfg(feature = "std")]
+mod rem;
+mod rng;
+#[cfg(feature = "std")]
+mod rollback;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "std")]
+mod shared;
+mod small;
+#[cfg(feature = "std")]
+mod sparse;
+mod storage;
+#[cfg(feature = "std")]
+mod unify;
+mod union_find;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+mod weighted;
 
-    #[test]
-    fn should_get_count() {
-        let n = 10;
-        let graph = Graph::new(n);
-        assert_eq!(n, graph.count());
-    }
-
-    #[test]
-    fn should_connect() {
-        let mut graph = Graph::new(10);
-        assert!(graph.connected(0, 0));
-        assert!(!graph.connected(0, 1));
-        graph.connect(0, 1);
-        assert!(graph.connected(0, 1));
-    }
-}
+#[cfg(feature = "std")]
+pub use aggregate::AggregateGraph;
+pub use array::ArrayGraph;
+pub use builder::GraphBuilder;
+pub use error::WufError;
+pub use frozen::FrozenPartition;
+#[cfg(feature = "rkyv")]
+pub use frozen::ArchivedFrozenPartition;
+pub use graph::{Compression, Graph, Linking};
+#[cfg(feature = "std")]
+pub use group::{Group, GroupGraph};
+pub use index::Index;
+#[cfg(feature = "rkyv")]
+pub use index::ArchiveIndex;
+#[cfg(feature = "std")]
+pub use keyed::KeyedGraph;
+#[cfg(all(feature = "mmap", unix))]
+pub use mapped::{MappedGraph, MappedStorage};
+pub use node::NodeId;
+#[cfg(feature = "std")]
+pub use packed::PackedGraph;
+#[cfg(feature = "std")]
+pub use parity::{ParityGraph, Relation};
+#[cfg(feature = "std")]
+pub use payload::PayloadGraph;
+#[cfg(feature = "std")]
+pub use persistent::PersistentGraph;
+#[cfg(feature = "std")]
+pub use quick::{QuickFind, QuickUnion};
+#[cfg(feature = "std")]
+pub use randomized::RandomizedGraph;
+#[cfg(feature = "std")]
+pub use rem::RemGraph;
+#[cfg(feature = "std")]
+pub use rollback::RollbackGraph;
+#[cfg(feature = "std")]
+pub use shared::SharedGraph;
+pub use small::SmallGraph;
+#[cfg(feature = "std")]
+pub use sparse::SparseGraph;
+pub use storage::{Storage, StorageGraph};
+#[cfg(feature = "std")]
+pub use unify::{UnificationTable, UnifyKey, UnifyValue};
+pub use union_find::UnionFind;
+#[cfg(feature = "std")]
+pub use weighted::WeightedGraph;
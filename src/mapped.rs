@@ -0,0 +1,278 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::mem;
+use std::os::raw::{c_int, c_long, c_void};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+use storage::{Storage, StorageGraph};
+
+// identifies the files of a MappedGraph, and the width of their words
+#[cfg(target_pointer_width = "64")]
+const MAGIC: usize = 0x5755_465f_4d41_5000 | mem::size_of::<usize>();
+#[cfg(not(target_pointer_width = "64"))]
+const MAGIC: usize = 0x5755_4600 | mem::size_of::<usize>();
+// number of words of the header (magic number and nodes)
+const HEADER: usize = 2;
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "netbsd", target_os = "solaris", target_os = "illumos"))]
+const MS_SYNC: c_int = 4;
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const MS_SYNC: c_int = 16;
+#[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+const MS_SYNC: c_int = 0;
+#[cfg(target_os = "openbsd")]
+const MS_SYNC: c_int = 2;
+#[cfg(not(any(
+    target_os = "linux", target_os = "android", target_os = "netbsd", target_os = "solaris",
+    target_os = "illumos", target_os = "macos", target_os = "ios", target_os = "freebsd",
+    target_os = "dragonfly", target_os = "openbsd"
+)))]
+compile_error!("the `mmap` feature does not support this operating system");
+
+extern "C" {
+    fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: c_long) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
+}
+
+/// Storage of the nodes of a `MappedGraph`, living in a memory-mapped file
+/// (with the `mmap` feature, on Unix), where the header of the file is
+/// followed by the parent and the size of each node.
+#[derive(Debug)]
+pub struct MappedStorage {
+    words: *mut usize,  // header followed by the parent and size of each node
+    len: usize,         // number of words mapped
+    _file: File         // file the words are mapped from
+}
+
+impl MappedStorage {
+
+    /// Creates (or truncates) the file at the given path, and returns the
+    /// storage of the given number of nodes mapped from it (all zero).
+    /// 
+    /// # Arguments
+    /// * `path` Path of the file the nodes are stored in.
+    /// * `n` Number of nodes belonging to the graph.
+    pub fn create<P: AsRef<Path>>(path: P, n: usize) -> io::Result<MappedStorage> {
+        let len = n.checked_mul(2)
+            .and_then(|words| words.checked_add(HEADER))
+            .filter(|&words| words.checked_mul(mem::size_of::<usize>()).is_some())
+            .ok_or_else(|| invalid_data("too many nodes"))?;
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len((len * mem::size_of::<usize>()) as u64)?;
+        let mut storage = MappedStorage::map(file, len)?;
+        let words = storage.words_mut();
+        words[0] = MAGIC;
+        words[1] = n;
+        Ok(storage)
+    }
+
+    /// Opens the file at the given path, previously created with `create`,
+    /// and returns the storage mapped from it.
+    /// 
+    /// Returns an error of kind `InvalidData` if the file does not contain the
+    /// nodes of a graph (created on a machine with the same word size).
+    /// 
+    /// # Arguments
+    /// * `path` Path of the file the nodes are stored in.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MappedStorage> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let bytes = file.metadata()?.len();
+        let width = mem::size_of::<usize>() as u64;
+        if bytes < HEADER as u64 * width || !bytes.is_multiple_of(width) || bytes / width > usize::MAX as u64 {
+            return Err(invalid_data("not a wuf mapped graph"));
+        }
+        let len = (bytes / width) as usize;
+        let storage = MappedStorage::map(file, len)?;
+        let words = storage.words();
+        if words[0] != MAGIC || !(len - HEADER).is_multiple_of(2) || (len - HEADER) / 2 != words[1] {
+            return Err(invalid_data("not a wuf mapped graph"));
+        }
+        Ok(storage)
+    }
+
+    /// Writes the changes still in memory to the file, returning once they
+    /// are on disk.
+    pub fn flush(&self) -> io::Result<()> {
+        let bytes = self.len * mem::size_of::<usize>();
+        if unsafe { msync(self.words as *mut c_void, bytes, MS_SYNC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Maps the given number of words of the given file.
+    fn map(file: File, len: usize) -> io::Result<MappedStorage> {
+        let bytes = len * mem::size_of::<usize>();
+        let words = unsafe {
+            mmap(ptr::null_mut(), bytes, PROT_READ | PROT_WRITE, MAP_SHARED, file.as_raw_fd(), 0)
+        };
+        // mmap returns MAP_FAILED (all bits set) on failure
+        if words as usize == usize::MAX {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(MappedStorage { words: words as *mut usize, len, _file: file })
+    }
+
+    fn words(&self) -> &[usize] {
+        // the mapping is page aligned and lives as long as the storage
+        unsafe { slice::from_raw_parts(self.words, self.len) }
+    }
+
+    fn words_mut(&mut self) -> &mut [usize] {
+        unsafe { slice::from_raw_parts_mut(self.words, self.len) }
+    }
+}
+
+impl Storage for MappedStorage {
+    fn len(&self) -> usize {
+        self.words()[1]
+    }
+
+    fn parent(&self, id: usize) -> usize {
+        self.words()[HEADER + 2 * id]
+    }
+
+    fn set_parent(&mut self, id: usize, parent: usize) {
+        self.words_mut()[HEADER + 2 * id] = parent;
+    }
+
+    fn size(&self, id: usize) -> usize {
+        self.words()[HEADER + 2 * id + 1]
+    }
+
+    fn set_size(&mut self, id: usize, size: usize) {
+        self.words_mut()[HEADER + 2 * id + 1] = size;
+    }
+}
+
+impl Drop for MappedStorage {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.words as *mut c_void, self.len * mem::size_of::<usize>());
+        }
+    }
+}
+
+// the mapping is owned by the storage, as if it were a Vec
+unsafe impl Send for MappedStorage {}
+unsafe impl Sync for MappedStorage {}
+
+/// Weighted union-find whose parents and sizes live in a memory-mapped file
+/// (with the `mmap` feature, on Unix), so that the number of nodes is only
+/// bounded by the disk, and the operating system pages the nodes in and out
+/// of memory as needed.
+/// 
+/// Every change is written to the file (eventually flushed by the operating
+/// system, or explicitly with `flush`), so that a job that stops for any
+/// reason can resume from the file with `open` (which recomputes the sizes of
+/// the roots, since a job can stop between the two writes of `connect`).
+/// Roots are linked by size and paths are compressed by halving, to keep the
+/// number of pages touched by each operation small.
+/// 
+/// # Example
+/// ```
+/// let path = std::env::temp_dir().join("wuf-mapped-example");
+/// let mut graph = wuf::MappedGraph::create(&path, 10).unwrap();
+/// graph.connect(0, 1);
+/// drop(graph);
+/// let mut graph = wuf::MappedGraph::open(&path).unwrap();
+/// assert!(graph.connected(1, 0));
+/// assert_eq!(9, graph.component_count());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub type MappedGraph = StorageGraph<MappedStorage>;
+
+impl StorageGraph<MappedStorage> {
+
+    /// Creates (or truncates) the file at the given path, and returns a new
+    /// MappedGraph with the given number of nodes stored in it.
+    /// 
+    /// # Arguments
+    /// * `path` Path of the file the nodes are stored in.
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let path = std::env::temp_dir().join("wuf-mapped-create");
+    /// let graph = wuf::MappedGraph::create(&path, 10).unwrap();
+    /// assert_eq!(10, graph.count());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn create<P: AsRef<Path>>(path: P, n: usize) -> io::Result<MappedGraph> {
+        MappedStorage::create(path, n).map(StorageGraph::new)
+    }
+
+    /// Opens the file at the given path, previously created with `create`,
+    /// and returns the MappedGraph stored in it, resuming from its state.
+    /// 
+    /// The sizes of the roots are recomputed, and every node is pointed
+    /// directly to its root, in a single pass over the file that takes no
+    /// memory besides the mapping (see `StorageGraph::recover`). Returns an
+    /// error of kind `InvalidData` if the file does not contain a graph
+    /// (created on a machine with the same word size), or if its parents are
+    /// out of bounds or form a cycle.
+    /// 
+    /// # Arguments
+    /// * `path` Path of the file the nodes are stored in.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MappedGraph> {
+        let storage = MappedStorage::open(path)?;
+        StorageGraph::recover(storage).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Writes the changes still in memory to the file, returning once they
+    /// are on disk.
+    pub fn flush(&self) -> io::Result<()> {
+        self.storage().flush()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn should_resume_from_file() {
+        let path = env::temp_dir().join(format!("wuf-mapped-{}", ::std::process::id()));
+        {
+            let mut graph = MappedGraph::create(&path, 6).unwrap();
+            for &(a, b) in &[(0, 1), (2, 3), (1, 3)] {
+                graph.connect(a, b);
+            }
+            graph.flush().unwrap();
+        }
+        let mut graph = MappedGraph::open(&path).unwrap();
+        assert_eq!(6, graph.count());
+        assert_eq!(3, graph.component_count());
+        assert_eq!(4, graph.component_size(2));
+        assert!(graph.connected(0, 2));
+        assert!(!graph.connected(0, 4));
+        // a job stopping after growing the size of a root, before linking the
+        // other root to it, leaves a stale size
+        let root = graph.find(4);
+        let mut storage = graph.into_storage();
+        storage.set_size(root, 2);
+        drop(storage);
+        let mut graph = MappedGraph::open(&path).unwrap();
+        assert_eq!(3, graph.component_count());
+        assert_eq!(1, graph.component_size(4));
+        assert_eq!(4, graph.component_size(0));
+        drop(graph);
+        fs::write(&path, b"not a graph").unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, MappedGraph::open(&path).unwrap_err().kind());
+        fs::remove_file(&path).unwrap();
+    }
+}
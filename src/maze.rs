@@ -0,0 +1,87 @@
+//! Perfect mazes generated by randomized Kruskal's algorithm.
+//! 
+//! The cells of a `width`-by-`height` maze are numbered in row-major order
+//! (the cell at column `x` and row `y` is `y * width + x`), and a maze is the
+//! list of its passages, each a pair of adjacent cells (the lower first).
+//! In a perfect maze every cell is reachable from every other cell through
+//! exactly one path.
+
+use graph::Graph;
+use rng::{self, SplitMix64};
+
+/// Returns the passages of a perfect maze of the given size, with a random
+/// seed (see `generate_with_seed`).
+/// 
+/// # Arguments
+/// * `width` Number of columns of the maze.
+/// * `height` Number of rows of the maze.
+pub fn generate(width: usize, height: usize) -> Vec<(usize, usize)> {
+    generate_with_seed(width, height, rng::random_seed())
+}
+
+/// Returns the passages of a perfect maze of the given size, in the order
+/// the walls were removed, where the same seed always produces the same maze.
+/// 
+/// Every wall between two adjacent cells is visited in a pseudorandom order,
+/// and removed only if the two cells are not yet connected.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the maze.
+/// * `height` Number of rows of the maze.
+/// * `seed` Seed of the pseudorandom order in which walls are visited.
+/// 
+/// # Example
+/// ```
+/// let passages = wuf::maze::generate_with_seed(4, 3, 42);
+/// // a perfect maze is a spanning tree of the cells
+/// assert_eq!(4 * 3 - 1, passages.len());
+/// ```
+pub fn generate_with_seed(width: usize, height: usize, seed: u64) -> Vec<(usize, usize)> {
+    let cells = width.checked_mul(height).expect("maze too large");
+    let mut walls = Vec::with_capacity(2 * cells);
+    for y in 0..height {
+        for x in 0..width {
+            let cell = y * width + x;
+            if x + 1 < width {
+                walls.push((cell, cell + 1));
+            }
+            if y + 1 < height {
+                walls.push((cell, cell + width));
+            }
+        }
+    }
+    SplitMix64::new(seed).shuffle(&mut walls);
+    let mut graph = Graph::new(cells);
+    let mut passages = Vec::with_capacity(cells.saturating_sub(1));
+    for (a, b) in walls {
+        if graph.connect(a, b).is_some() {
+            passages.push((a, b));
+            if graph.component_count() == 1 {
+                break;
+            }
+        }
+    }
+    passages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_perfect_maze() {
+        let (width, height) = (9, 7);
+        let passages = generate_with_seed(width, height, 3);
+        assert_eq!(passages, generate_with_seed(width, height, 3));
+        assert_ne!(passages, generate_with_seed(width, height, 4));
+        let mut graph = Graph::new(width * height);
+        for &(a, b) in &passages {
+            // passages join adjacent cells, without cycles
+            assert!(b == a + 1 && a % width + 1 < width || b == a + width);
+            assert!(graph.connect(a, b).is_some());
+        }
+        assert_eq!(1, graph.component_count());
+        assert!(generate(1, 1).is_empty());
+        assert!(generate(0, 5).is_empty());
+    }
+}
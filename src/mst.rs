@@ -0,0 +1,388 @@
+//! Minimum (and maximum) spanning trees of weighted graphs.
+//! 
+//! Edges are given as `(u, v, w)` triples of two node IDs and a weight, and
+//! the spanning trees are returned as the indices of the chosen edges. If the
+//! graph is not connected, the result is a spanning forest, with a tree for
+//! every component.
+
+use std::cmp::Ordering;
+use std::thread;
+
+use cluster::Dendrogram;
+use graph::Graph;
+
+/// Returns the indices of the edges of a minimum spanning tree (or forest) of
+/// the graph with the given number of nodes, in ascending order of weight,
+/// using Kruskal's algorithm.
+/// 
+/// Edges of equal weight are chosen in order of index, while incomparable
+/// weights (such as `NaN`) are treated as equal.
+/// 
+/// Panics if an edge refers to a node out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `edges` Weighted edges of the graph.
+/// 
+/// # Example
+/// ```
+/// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5)];
+/// assert_eq!(vec![1, 2, 3], wuf::mst::kruskal(4, &edges));
+/// ```
+pub fn kruskal<W: PartialOrd>(n: usize, edges: &[(usize, usize, W)]) -> Vec<usize> {
+    spanning_tree(n, edges, sorted_edges(edges, false))
+}
+
+/// Returns the indices of the edges of a maximum spanning tree (or forest) of
+/// the graph with the given number of nodes, in descending order of weight,
+/// using Kruskal's algorithm (see `kruskal`).
+/// 
+/// Panics if an edge refers to a node out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `edges` Weighted edges of the graph.
+/// 
+/// # Example
+/// ```
+/// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5)];
+/// assert_eq!(vec![3, 0, 2], wuf::mst::kruskal_max(4, &edges));
+/// ```
+pub fn kruskal_max<W: PartialOrd>(n: usize, edges: &[(usize, usize, W)]) -> Vec<usize> {
+    spanning_tree(n, edges, sorted_edges(edges, true))
+}
+
+/// Returns the indices of the edges of a minimum spanning tree (or forest) of
+/// the graph with the given number of nodes, in ascending order of index,
+/// using Borůvka's algorithm with as many threads as the available
+/// parallelism (see `boruvka_with_threads`).
+/// 
+/// Panics if an edge refers to a node out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `edges` Weighted edges of the graph.
+/// 
+/// # Example
+/// ```
+/// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5)];
+/// assert_eq!(vec![1, 2, 3], wuf::mst::boruvka(4, &edges));
+/// ```
+pub fn boruvka<W: PartialOrd + Sync>(n: usize, edges: &[(usize, usize, W)]) -> Vec<usize> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    boruvka_with_threads(n, edges, threads)
+}
+
+/// Returns the indices of the edges of a minimum spanning tree (or forest) of
+/// the graph with the given number of nodes, in ascending order of index,
+/// using Borůvka's algorithm with the given number of threads.
+/// 
+/// Every round splits the edges among the threads, that find the cheapest
+/// edge leaving each component in parallel, and then connects the components
+/// through their cheapest edges, so that at least half of the components are
+/// merged by every round. Every thread needs memory linear in the number of
+/// nodes. Edges of equal weight are ordered by index, so that the spanning
+/// tree is the same as the one returned by `kruskal`, while incomparable
+/// weights (such as `NaN`) are treated as equal.
+/// 
+/// Panics if an edge refers to a node out of bounds.
+/// 
+/// # Arguments
+/// * `n` Number of nodes belonging to the graph.
+/// * `edges` Weighted edges of the graph.
+/// * `threads` Number of threads, where 0 is treated as 1.
+/// 
+/// # Example
+/// ```
+/// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5)];
+/// assert_eq!(vec![1, 2, 3], wuf::mst::boruvka_with_threads(4, &edges, 2));
+/// ```
+pub fn boruvka_with_threads<W: PartialOrd + Sync>(
+    n: usize,
+    edges: &[(usize, usize, W)],
+    threads: usize
+) -> Vec<usize> {
+    let mut graph = Graph::new(n);
+    let mut tree = Vec::with_capacity(n.saturating_sub(1));
+    let chunk_size = edges.len().div_ceil(threads.max(1)).max(1);
+    while graph.component_count() > 1 {
+        let roots: Vec<usize> = (0..n).map(|id| graph.find(id)).collect();
+        let cheapest = thread::scope(|scope| {
+            let roots = &roots;
+            let handles: Vec<_> = edges.chunks(chunk_size).enumerate().map(|(i, chunk)| {
+                scope.spawn(move || cheapest_edges(edges, i * chunk_size, chunk, roots))
+            }).collect();
+            // merge the cheapest edges found by every thread
+            let mut cheapest = vec![NONE; n];
+            for handle in handles {
+                let found = handle.join().expect("thread panicked");
+                for (root, id) in found.into_iter().enumerate() {
+                    if id != NONE && (cheapest[root] == NONE || lighter(edges, id, cheapest[root])) {
+                        cheapest[root] = id;
+                    }
+                }
+            }
+            cheapest
+        });
+        let merged = tree.len();
+        for id in cheapest.into_iter().filter(|&id| id != NONE) {
+            let (a, b, _) = edges[id];
+            if graph.connect(a, b).is_some() {
+                tree.push(id);
+            }
+        }
+        // no edge leaves any component, the spanning forest is complete
+        if tree.len() == merged {
+            break;
+        }
+    }
+    tree.sort_unstable();
+    tree
+}
+
+// marks the absence of an edge
+const NONE: usize = usize::MAX;
+
+/// Returns the index of the cheapest edge of the given chunk (whose first
+/// edge has the given index) leaving the component of every root, or `NONE`.
+fn cheapest_edges<W: PartialOrd>(
+    edges: &[(usize, usize, W)],
+    start: usize,
+    chunk: &[(usize, usize, W)],
+    roots: &[usize]
+) -> Vec<usize> {
+    let mut cheapest = vec![NONE; roots.len()];
+    for (offset, &(a, b, _)) in chunk.iter().enumerate() {
+        let id = start + offset;
+        let (a_root, b_root) = (roots[a], roots[b]);
+        if a_root == b_root {
+            continue;
+        }
+        for &root in &[a_root, b_root] {
+            if cheapest[root] == NONE || lighter(edges, id, cheapest[root]) {
+                cheapest[root] = id;
+            }
+        }
+    }
+    cheapest
+}
+
+/// Returns true only if the first edge is lighter than the second edge, with
+/// ties broken by index.
+fn lighter<W: PartialOrd>(edges: &[(usize, usize, W)], a: usize, b: usize) -> bool {
+    match edges[a].2.partial_cmp(&edges[b].2).unwrap_or(Ordering::Equal) {
+        Ordering::Less => true,
+        Ordering::Equal => a < b,
+        Ordering::Greater => false
+    }
+}
+
+/// Kruskal reconstruction tree of a weighted graph, answering bottleneck
+/// (minimax) queries: the minimum, among the paths between two nodes, of the
+/// maximum weight of the edges along the path.
+/// 
+/// The leaves of the tree are the nodes of the graph, while every internal
+/// node is a merge performed by Kruskal's algorithm, weighted by the weight of
+/// the merging edge, so that the bottleneck between two nodes is the weight
+/// of their lowest common ancestor, found by binary lifting in logarithmic
+/// time.
+#[derive(Debug, Clone)]
+pub struct BottleneckTree<W> {
+    weights: Vec<W>,            // weight of every internal node
+    depths: Vec<usize>,         // depth of every node
+    ancestors: Vec<Vec<usize>>  // ancestor 2^k levels above every node
+}
+
+impl<W: PartialOrd + Clone> BottleneckTree<W> {
+
+    /// Returns the reconstruction tree of the graph with the given number of
+    /// nodes and the given weighted edges.
+    /// 
+    /// Panics if an edge refers to a node out of bounds.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `edges` Weighted edges of the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5)];
+    /// let tree = wuf::mst::BottleneckTree::new(5, &edges);
+    /// assert_eq!(Some(&2), tree.bottleneck(0, 1));
+    /// assert_eq!(Some(&5), tree.bottleneck(0, 3));
+    /// assert_eq!(None, tree.bottleneck(0, 4));
+    /// ```
+    pub fn new(n: usize, edges: &[(usize, usize, W)]) -> BottleneckTree<W> {
+        let merges = Dendrogram::new(n, edges).merges().to_vec();
+        let count = n + merges.len();
+        // roots of the tree are their own parent
+        let mut parents: Vec<usize> = (0..count).collect();
+        let mut weights = Vec::with_capacity(merges.len());
+        for (i, merge) in merges.into_iter().enumerate() {
+            parents[merge.a] = n + i;
+            parents[merge.b] = n + i;
+            weights.push(merge.weight);
+        }
+        // every parent has a greater ID than its children
+        let mut depths = vec![0; count];
+        for id in (0..count).rev() {
+            if parents[id] != id {
+                depths[id] = depths[parents[id]] + 1;
+            }
+        }
+        let height: usize = depths.iter().cloned().max().unwrap_or(0);
+        let levels = (usize::BITS - height.leading_zeros()) as usize;
+        let mut ancestors = vec![parents];
+        for k in 1..levels {
+            let previous = &ancestors[k - 1];
+            let next = previous.iter().map(|&ancestor| previous[ancestor]).collect();
+            ancestors.push(next);
+        }
+        BottleneckTree { weights, depths, ancestors }
+    }
+
+    /// Returns the number of nodes of the graph.
+    pub fn count(&self) -> usize {
+        self.depths.len() - self.weights.len()
+    }
+
+    /// Returns the minimum, among the paths between the two given nodes, of
+    /// the maximum weight of the edges along the path, or `None` if the nodes
+    /// are not connected or are the same node.
+    /// 
+    /// Panics if a node is out of bounds.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let edges = [(0, 1, 3.5), (1, 2, 0.5)];
+    /// let tree = wuf::mst::BottleneckTree::new(3, &edges);
+    /// assert_eq!(Some(&3.5), tree.bottleneck(2, 0));
+    /// ```
+    pub fn bottleneck(&self, a: usize, b: usize) -> Option<&W> {
+        let n = self.count();
+        assert!(a < n && b < n, "node out of bounds (count is {})", n);
+        let (mut a, mut b) = if self.depths[a] >= self.depths[b] { (a, b) } else { (b, a) };
+        // lift the deeper node to the depth of the other node
+        let mut lift = self.depths[a] - self.depths[b];
+        let mut k = 0;
+        while lift > 0 {
+            if lift & 1 == 1 {
+                a = self.ancestors[k][a];
+            }
+            lift >>= 1;
+            k += 1;
+        }
+        if a == b {
+            // a leaf is an ancestor only of itself
+            return None;
+        }
+        for k in (0..self.ancestors.len()).rev() {
+            if self.ancestors[k][a] != self.ancestors[k][b] {
+                a = self.ancestors[k][a];
+                b = self.ancestors[k][b];
+            }
+        }
+        let ancestor = self.ancestors[0][a];
+        if ancestor == a || ancestor != self.ancestors[0][b] {
+            // the nodes belong to different trees
+            return None;
+        }
+        Some(&self.weights[ancestor - n])
+    }
+}
+
+/// Returns the indices of the given edges sorted by weight, in ascending order
+/// or in descending order, with ties broken by index.
+pub(crate) fn sorted_edges<W: PartialOrd>(edges: &[(usize, usize, W)], descending: bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    // stable sort, so that edges of equal weight keep their relative order
+    order.sort_by(|&a, &b| {
+        let ordering = edges[a].2.partial_cmp(&edges[b].2).unwrap_or(Ordering::Equal);
+        if descending { ordering.reverse() } else { ordering }
+    });
+    order
+}
+
+/// Returns the indices of the edges, visited in the given order, that connect
+/// two different components.
+fn spanning_tree<W>(n: usize, edges: &[(usize, usize, W)], order: Vec<usize>) -> Vec<usize> {
+    let mut graph = Graph::new(n);
+    let mut tree = Vec::with_capacity(n.saturating_sub(1));
+    for id in order {
+        // a spanning tree is complete once every node is connected
+        if graph.component_count() <= 1 {
+            break;
+        }
+        let (a, b, _) = edges[id];
+        if graph.connect(a, b).is_some() {
+            tree.push(id);
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_span_forest() {
+        let edges = [
+            (0, 1, 1.5), (1, 2, 0.5), (0, 2, 1.0), (3, 4, 2.0), (4, 3, 0.1), (2, 2, 0.0)
+        ];
+        assert_eq!(vec![4, 1, 2], kruskal(5, &edges));
+        assert_eq!(vec![3, 0, 2], kruskal_max(5, &edges));
+        assert!(kruskal::<u32>(3, &[]).is_empty());
+    }
+
+    #[test]
+    fn should_match_kruskal() {
+        let n = 200;
+        let edges: Vec<(usize, usize, u64)> = (0..600u64).map(|i| {
+            let hash = ::rng::mix(i);
+            ((hash % n) as usize, ((hash >> 16) % n) as usize, (hash >> 32) % 50)
+        }).collect();
+        let mut expected = kruskal(n as usize, &edges);
+        expected.sort_unstable();
+        for &threads in &[0, 1, 2, 7, 1000] {
+            assert_eq!(expected, boruvka_with_threads(n as usize, &edges, threads));
+        }
+    }
+
+    #[test]
+    fn should_find_bottleneck() {
+        let n = 60;
+        let edges: Vec<(usize, usize, u64)> = (0..90u64).map(|i| {
+            let hash = ::rng::mix(i ^ 0xb0771e);
+            ((hash % n) as usize, ((hash >> 16) % n) as usize, (hash >> 32) % 100)
+        }).collect();
+        let tree = BottleneckTree::new(n as usize, &edges);
+        for a in 0..n as usize {
+            for b in 0..n as usize {
+                // the bottleneck is the lowest weight connecting the two nodes
+                let mut graph = Graph::new(n as usize);
+                let mut expected = None;
+                for id in sorted_edges(&edges, false) {
+                    let (u, v, ref w) = edges[id];
+                    graph.connect(u, v);
+                    if a != b && graph.connected(a, b) {
+                        expected = Some(w);
+                        break;
+                    }
+                }
+                assert_eq!(expected, tree.bottleneck(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn should_break_ties_by_index() {
+        let edges = [(0, 1, 1), (1, 2, 1), (0, 2, 1)];
+        assert_eq!(vec![0, 1], kruskal(3, &edges));
+        assert_eq!(vec![0, 1], kruskal_max(3, &edges));
+    }
+}
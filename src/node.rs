@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// tag assigned to the next graph created
+static NEXT_TAG: AtomicUsize = AtomicUsize::new(0);
+
+/// Strongly-typed handle to a node of a `Graph`.
+/// 
+/// Unlike a raw `usize`, a `NodeId` remembers the graph it was obtained from
+/// (or any clone of it), and using it with a different graph panics rather
+/// than silently referring to an unrelated node. So does using it after its
+/// node was retired, even once the ID is reused (see `Graph::remove_node`).
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::Graph::new(2);
+/// let a = graph.node(0).unwrap();
+/// let b = graph.add_node();
+/// graph.connect_nodes(a, b);
+/// assert!(graph.connected(a.index(), b.index()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId {
+    index: usize,   // raw ID of the node
+    tag: usize,     // tag of the graph the node belongs to
+    generation: u32 // number of times the ID was reused when obtained
+}
+
+impl NodeId {
+
+    /// Returns a new handle to the node with the given raw ID and generation
+    /// in the graph with the given tag.
+    pub(crate) fn new(index: usize, tag: usize, generation: u32) -> NodeId {
+        NodeId { index, tag, generation }
+    }
+
+    /// Returns the raw ID of the node.
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    /// Returns the tag of the graph the node belongs to.
+    pub(crate) fn tag(self) -> usize {
+        self.tag
+    }
+
+    /// Returns the number of times the ID of the node was reused when the
+    /// handle was obtained.
+    pub(crate) fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl From<NodeId> for usize {
+    fn from(id: NodeId) -> usize {
+        id.index
+    }
+}
+
+/// Returns a tag that was never returned before, used to tell graphs apart.
+pub(crate) fn next_tag() -> usize {
+    NEXT_TAG.fetch_add(1, Ordering::Relaxed)
+}
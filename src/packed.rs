@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use union_find::UnionFind;
+
+// number of upper bits of each word reserved to the rank
+const RANK_BITS: u32 = 6;
+// number of lower bits of each word reserved to the parent
+const PARENT_BITS: u32 = usize::BITS - RANK_BITS;
+const PARENT_MASK: usize = (1 << PARENT_BITS) - 1;
+
+/// Union-find where the rank of each node lives in the upper bits of the word
+/// storing its parent, so that every hop of `find` touches a single word.
+/// 
+/// Components are linked by rank (rather than by size) since ranks are
+/// bounded by `log2(n)` and fit in a handful of bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedGraph {
+    nodes: Vec<usize>,  // rank (upper bits) and parent (lower bits) of each node
+    components: usize   // number of disjoint components
+}
+
+impl PackedGraph {
+
+    /// The maximum number of nodes the graph can hold.
+    pub const MAX_NODES: usize = PARENT_MASK;
+
+    /// Returns a new PackedGraph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph, at most `MAX_NODES`.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::PackedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> PackedGraph {
+        let mut graph = PackedGraph { nodes: Vec::with_capacity(n), components: 0 };
+        graph.add_nodes(n);
+        graph
+    }
+
+    /// Adds `k` new nodes, not connected to any other node, and returns the
+    /// range of their IDs.
+    /// 
+    /// Panics if the number of nodes would exceed `MAX_NODES`.
+    /// 
+    /// # Arguments
+    /// * `k` Number of nodes to add.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// assert_eq!(10..15, graph.add_nodes(5));
+    /// ```
+    pub fn add_nodes(&mut self, k: usize) -> Range<usize> {
+        let start = self.nodes.len();
+        let end = start.checked_add(k).filter(|&end| end <= Self::MAX_NODES)
+            .expect("number of nodes exceeds the capacity of the packed graph");
+        // every new node is a root of rank 0
+        self.nodes.extend(start..end);
+        self.components += k;
+        start..end
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::PackedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the rank of the given node, an upper bound on the height of
+    /// its subtree.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// let root = graph.connect(0, 1).unwrap();
+    /// assert_eq!(1, graph.rank(root));
+    /// ```
+    pub fn rank(&self, id: usize) -> usize {
+        self.nodes[id] >> PARENT_BITS
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        let a_rank = self.rank(a_root);
+        let b_rank = self.rank(b_root);
+        // link the root of lower rank below the root of higher rank
+        let (root, child) = if a_rank < b_rank {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.set_parent(child, root);
+        if a_rank == b_rank {
+            self.nodes[root] += 1 << PARENT_BITS;
+        }
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PackedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        let mut node = id;
+        loop {
+            let parent = self.parent(node);
+            if parent == node {
+                return node;
+            }
+            // make every other node in path point to its grandparent
+            let grandparent = self.parent(parent);
+            self.set_parent(node, grandparent);
+            node = grandparent;
+        }
+    }
+
+    /// Returns the parent of the given node.
+    fn parent(&self, id: usize) -> usize {
+        self.nodes[id] & PARENT_MASK
+    }
+
+    /// Sets the parent of the given node, preserving its rank.
+    fn set_parent(&mut self, id: usize, parent: usize) {
+        self.nodes[id] = (self.nodes[id] & !PARENT_MASK) | parent;
+    }
+}
+
+impl UnionFind for PackedGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        PackedGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        PackedGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect() {
+        let mut graph = PackedGraph::new(8);
+        for id in 0..4 {
+            graph.connect(2 * id, 2 * id + 1);
+        }
+        graph.connect(0, 2);
+        graph.connect(4, 6);
+        graph.connect(0, 4);
+        assert_eq!(1, graph.component_count());
+        let root = graph.find(7);
+        assert_eq!(3, graph.rank(root));
+        assert!((0..8).all(|id| graph.find(id) == root));
+        // compression must not alter the rank of the root
+        assert_eq!(3, graph.rank(root));
+    }
+}
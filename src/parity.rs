@@ -0,0 +1,183 @@
+use error::WufError;
+
+/// Relation between two nodes of a `ParityGraph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    /// The two nodes belong to the same side (have the same color).
+    Same,
+    /// The two nodes belong to opposite sides (have different colors).
+    Different
+}
+
+/// Union-find where every node has a parity relative to the root of its
+/// component, so that nodes can be asserted to be on the same side or on
+/// opposite sides, and inconsistent assertions are reported as
+/// contradictions.
+/// 
+/// This solves 2-coloring problems: a set of relations is consistent only if
+/// the graph of `Different` relations is bipartite once the `Same` relations
+/// are contracted.
+/// 
+/// # Example
+/// ```
+/// use wuf::{ParityGraph, Relation, WufError};
+/// 
+/// let mut graph = ParityGraph::new(3);
+/// graph.relate(0, 1, Relation::Different).unwrap();
+/// graph.relate(1, 2, Relation::Different).unwrap();
+/// assert_eq!(Some(Relation::Same), graph.relation(0, 2));
+/// let err = graph.relate(0, 2, Relation::Different).unwrap_err();
+/// assert_eq!(WufError::Contradiction { a: 0, b: 2 }, err);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParityGraph {
+    parents: Vec<usize>,    // parent of each node, roots are their own parent
+    parities: Vec<bool>,    // whether each node is on the opposite side of its parent
+    sizes: Vec<usize>,      // size of the tree of each root
+    components: usize       // number of disjoint components
+}
+
+impl ParityGraph {
+
+    /// Returns a new ParityGraph with the given number of nodes, without
+    /// relations.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::ParityGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> ParityGraph {
+        ParityGraph {
+            parents: (0..n).collect(),
+            parities: vec![false; n],
+            sizes: vec![1; n],
+            components: n
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the root of the given node, and whether the node is on the
+    /// opposite side of its root.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> (usize, bool) {
+        let mut root = id;
+        let mut parity = false;
+        while self.parents[root] != root {
+            parity ^= self.parities[root];
+            root = self.parents[root];
+        }
+        // make every node in path point to the root, with its own parity
+        let mut node = id;
+        let mut node_parity = parity;
+        while self.parents[node] != root && node != root {
+            let parent = self.parents[node];
+            let parent_parity = node_parity ^ self.parities[node];
+            self.parents[node] = root;
+            self.parities[node] = node_parity;
+            node = parent;
+            node_parity = parent_parity;
+        }
+        (root, parity)
+    }
+
+    /// Returns the relation between the two given nodes implied by the
+    /// relations asserted so far, or `None` if they are not related.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn relation(&mut self, a: usize, b: usize) -> Option<Relation> {
+        let (a_root, a_parity) = self.find(a);
+        let (b_root, b_parity) = self.find(b);
+        if a_root != b_root {
+            None
+        } else if a_parity == b_parity {
+            Some(Relation::Same)
+        } else {
+            Some(Relation::Different)
+        }
+    }
+
+    /// Asserts the given relation between the two given nodes.
+    /// 
+    /// Returns true only if the two nodes belonged to different components,
+    /// or an error if the relation contradicts the relations asserted so far
+    /// (in which case the graph is not modified).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// * `relation` Relation between the two nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::{ParityGraph, Relation};
+    /// 
+    /// let mut graph = ParityGraph::new(10);
+    /// assert_eq!(Ok(true), graph.relate(0, 1, Relation::Same));
+    /// assert_eq!(Ok(false), graph.relate(1, 0, Relation::Same));
+    /// assert!(graph.relate(1, 0, Relation::Different).is_err());
+    /// ```
+    pub fn relate(&mut self, a: usize, b: usize, relation: Relation) -> Result<bool, WufError> {
+        let (a_root, a_parity) = self.find(a);
+        let (b_root, b_parity) = self.find(b);
+        let different = relation == Relation::Different;
+        if a_root == b_root {
+            return if (a_parity != b_parity) == different {
+                Ok(false)
+            } else {
+                Err(WufError::Contradiction { a, b })
+            };
+        }
+        // link the smaller tree below the larger one
+        let (root, child) = if self.sizes[a_root] < self.sizes[b_root] {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.parents[child] = root;
+        self.parities[child] = a_parity ^ b_parity ^ different;
+        self.sizes[root] += self.sizes[child];
+        self.components -= 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_odd_cycles() {
+        // an even cycle is bipartite, while an odd cycle is not
+        let mut graph = ParityGraph::new(6);
+        for id in 0..4 {
+            assert_eq!(Ok(id < 3), graph.relate(id, (id + 1) % 4, Relation::Different));
+        }
+        assert_eq!(Some(Relation::Same), graph.relation(0, 2));
+        assert_eq!(Some(Relation::Different), graph.relation(3, 2));
+        assert_eq!(None, graph.relation(0, 4));
+        graph.relate(4, 5, Relation::Same).unwrap();
+        graph.relate(5, 0, Relation::Different).unwrap();
+        let snapshot = graph.clone();
+        assert!(graph.relate(4, 1, Relation::Different).is_err());
+        assert_eq!(snapshot, graph);
+        assert_eq!(Some(Relation::Same), graph.relation(4, 1));
+        assert_eq!(1, graph.component_count());
+    }
+}
@@ -0,0 +1,296 @@
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use error::WufError;
+use graph::Graph;
+use index::Index;
+use rng::mix;
+
+impl<I: Index> Graph<I> {
+
+    /// Returns true only if the two graphs have the same number of nodes and
+    /// partition them into the same components, regardless of the shape of
+    /// their trees (or of their index types), otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `other` The graph to compare the partition with.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut a = wuf::Graph::new(4);
+    /// a.connect(0, 1);
+    /// a.connect(1, 2);
+    /// let mut b = wuf::Graph::<u8>::with_nodes(4);
+    /// b.connect(2, 0);
+    /// assert!(!a.same_partition(&b));
+    /// b.connect(1, 2);
+    /// assert!(a.same_partition(&b));
+    /// ```
+    pub fn same_partition<J: Index>(&self, other: &Graph<J>) -> bool {
+        // labels in order of first appearance are unique for each partition
+        self.count() == other.count()
+            && self.component_count() == other.component_count()
+            && self.labels() == other.labels()
+    }
+
+    /// Returns true only if the two graphs have the same number of nodes and
+    /// every component of this graph is contained in a single component of
+    /// the other graph, otherwise returns false. Every partition is a
+    /// refinement of itself.
+    /// 
+    /// # Arguments
+    /// * `other` The graph whose partition is expected to be coarser.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut fine = wuf::Graph::new(4);
+    /// fine.connect(0, 1);
+    /// let mut coarse = wuf::Graph::new(4);
+    /// coarse.connect(0, 1);
+    /// coarse.connect(1, 2);
+    /// assert!(fine.is_refinement_of(&coarse));
+    /// assert!(!coarse.is_refinement_of(&fine));
+    /// ```
+    pub fn is_refinement_of<J: Index>(&self, other: &Graph<J>) -> bool {
+        if self.count() != other.count() || self.component_count() < other.component_count() {
+            return false;
+        }
+        // the component of the other graph containing each root, if visited
+        let mut containing = vec![usize::MAX; self.count()];
+        (0..self.count()).all(|id| {
+            let root = self.find_ref(id);
+            let other_root = other.find_ref(id);
+            if containing[root] == usize::MAX {
+                containing[root] = other_root;
+            }
+            containing[root] == other_root
+        })
+    }
+
+    /// Returns a new graph whose components are the non-empty intersections
+    /// of the components of the two graphs, so that two nodes are connected
+    /// only if they are connected in both graphs.
+    /// 
+    /// Returns an error if the two graphs have a different number of nodes.
+    /// 
+    /// # Arguments
+    /// * `other` The graph to intersect the partition with.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut by_email = wuf::Graph::new(4);
+    /// by_email.connect(0, 1);
+    /// by_email.connect(1, 2);
+    /// let mut by_phone = wuf::Graph::new(4);
+    /// by_phone.connect(1, 2);
+    /// by_phone.connect(2, 3);
+    /// let meet = by_email.meet(&by_phone).unwrap();
+    /// assert_eq!(vec![vec![0], vec![1, 2], vec![3]], meet.into_sets());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn meet<J: Index>(&self, other: &Graph<J>) -> Result<Graph<I>, WufError> {
+        if self.count() != other.count() {
+            return Err(WufError::LengthMismatch { len: other.count(), count: self.count() });
+        }
+        let mut meet = Graph::with_nodes(self.count());
+        // first node found in each pair of components
+        let mut first = HashMap::new();
+        for id in 0..self.count() {
+            let roots = (self.find_ref(id), other.find_ref(id));
+            let first = *first.entry(roots).or_insert(id);
+            meet.connect(first, id);
+        }
+        Ok(meet)
+    }
+
+    /// Returns a new graph whose components are the smallest unions of
+    /// components of both graphs, so that two nodes are connected if they are
+    /// connected in either graph (directly or through a chain of components).
+    /// 
+    /// Returns an error if the two graphs have a different number of nodes.
+    /// 
+    /// # Arguments
+    /// * `other` The graph to combine the partition with.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut stage_a = wuf::Graph::new(5);
+    /// stage_a.connect(0, 1);
+    /// let mut stage_b = wuf::Graph::new(5);
+    /// stage_b.connect(1, 2);
+    /// stage_b.connect(3, 4);
+    /// let join = stage_a.join(&stage_b).unwrap();
+    /// assert_eq!(vec![vec![0, 1, 2], vec![3, 4]], join.into_sets());
+    /// ```
+    pub fn join<J: Index>(&self, other: &Graph<J>) -> Result<Graph<I>, WufError> {
+        let mut join = self.clone();
+        join.merge(other)?;
+        Ok(join)
+    }
+
+    /// Connects every node to its root in the given graph (such as a shard
+    /// built independently over the same nodes), so that the components of
+    /// this graph become the ones of their join (see `join`), and returns the
+    /// number of merges.
+    /// 
+    /// Returns an error, without modifying the graph, if the two graphs have a
+    /// different number of nodes.
+    /// 
+    /// # Arguments
+    /// * `shard` The graph whose components are merged into this graph.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::thread;
+    /// 
+    /// let edges = [(0, 1), (2, 3), (1, 2), (5, 6), (6, 5), (3, 0)];
+    /// let shards: Vec<wuf::Graph> = thread::scope(|scope| {
+    ///     let handles: Vec<_> = edges.chunks(2).map(|chunk| {
+    ///         scope.spawn(move || wuf::Graph::from_edges(8, chunk.iter().cloned()))
+    ///     }).collect();
+    ///     handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    /// });
+    /// let mut graph = wuf::Graph::new(8);
+    /// for shard in &shards {
+    ///     graph.merge(shard).unwrap();
+    /// }
+    /// assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]], graph.into_sets());
+    /// ```
+    pub fn merge<J: Index>(&mut self, shard: &Graph<J>) -> Result<usize, WufError> {
+        if self.count() != shard.count() {
+            return Err(WufError::LengthMismatch { len: shard.count(), count: self.count() });
+        }
+        // connecting each node to its root in the shard merges every
+        // component of the shard
+        Ok(self.connect_all((0..self.count()).map(|id| (id, shard.find_ref(id)))))
+    }
+
+    /// Returns a hash of the partition, which depends neither on the shape of
+    /// the trees nor on the index type, so that graphs with the same
+    /// components (see `same_partition`) have the same hash.
+    /// 
+    /// The hash is stable across runs and platforms, hence it can be stored
+    /// alongside cached results, but it is not suitable for cryptography.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut a = wuf::Graph::new(4);
+    /// a.connect(0, 1);
+    /// a.connect(1, 2);
+    /// let mut b = wuf::Graph::<u8>::with_nodes(4);
+    /// b.connect(2, 1);
+    /// b.connect(0, 2);
+    /// assert_eq!(a.canonical_hash(), b.canonical_hash());
+    /// ```
+    pub fn canonical_hash(&self) -> u64 {
+        // hash the labels in order of first appearance, unique to the partition
+        self.labels().into_iter().fold(mix(self.count() as u64), |hash, label| {
+            mix(hash.rotate_left(5) ^ label as u64)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_compare_partitions() {
+        let mut a = Graph::new(6);
+        let mut b = Graph::new(6);
+        assert!(a.same_partition(&b));
+        for &(x, y) in &[(0, 5), (5, 3), (2, 4)] {
+            a.connect(x, y);
+        }
+        for &(x, y) in &[(4, 2), (3, 0), (0, 5)] {
+            b.connect(x, y);
+        }
+        assert!(a.same_partition(&b));
+        assert!(b.same_partition(&a));
+        assert!(!a.same_partition(&Graph::new(7)));
+        b.connect(1, 2);
+        assert!(!a.same_partition(&b));
+    }
+
+    #[test]
+    fn should_check_refinement() {
+        let mut fine = Graph::new(6);
+        let mut coarse = Graph::new(6);
+        assert!(fine.is_refinement_of(&coarse));
+        for &(x, y) in &[(0, 1), (2, 3)] {
+            fine.connect(x, y);
+        }
+        for &(x, y) in &[(3, 1), (0, 2), (2, 1), (4, 5)] {
+            coarse.connect(x, y);
+        }
+        assert!(fine.is_refinement_of(&coarse));
+        assert!(!coarse.is_refinement_of(&fine));
+        assert!(!fine.is_refinement_of(&Graph::new(7)));
+        fine.connect(3, 4);
+        assert!(!fine.is_refinement_of(&coarse));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn should_meet() {
+        let mut a = Graph::new(8);
+        let mut b = Graph::<u16>::with_nodes(8);
+        for &(x, y) in &[(0, 1), (1, 2), (2, 3), (4, 5), (6, 7)] {
+            a.connect(x, y);
+        }
+        for &(x, y) in &[(0, 2), (2, 4), (3, 5), (1, 6)] {
+            b.connect(x, y);
+        }
+        let meet = a.meet(&b).unwrap();
+        assert!(meet.is_refinement_of(&a));
+        assert!(meet.is_refinement_of(&b));
+        assert_eq!(vec![vec![0, 2], vec![1], vec![3], vec![4], vec![5], vec![6], vec![7]], meet.into_sets());
+        assert_eq!(
+            Err(WufError::LengthMismatch { len: 9, count: 8 }),
+            a.meet(&Graph::new(9)).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn should_join() {
+        let mut a = Graph::new(8);
+        let mut b = Graph::<u16>::with_nodes(8);
+        for &(x, y) in &[(0, 1), (2, 3), (6, 7)] {
+            a.connect(x, y);
+        }
+        for &(x, y) in &[(1, 2), (4, 5)] {
+            b.connect(x, y);
+        }
+        let join = a.join(&b).unwrap();
+        assert!(a.is_refinement_of(&join));
+        assert!(b.is_refinement_of(&join));
+        assert!(join.same_partition(&b.join(&a).unwrap()));
+        let mut merged = Graph::new(8);
+        assert_eq!(3, merged.merge(&a).unwrap());
+        assert_eq!(2, merged.merge(&b).unwrap());
+        assert_eq!(0, merged.merge(&b).unwrap());
+        assert!(merged.same_partition(&join));
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4, 5], vec![6, 7]], join.into_sets());
+        assert_eq!(
+            Err(WufError::LengthMismatch { len: 9, count: 8 }),
+            a.join(&Graph::new(9)).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn should_hash_partition() {
+        let mut a = Graph::new(6);
+        let mut b = Graph::with_linking(6, ::Linking::Rank);
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        assert_ne!(a.canonical_hash(), Graph::new(5).canonical_hash());
+        for &(x, y) in &[(0, 5), (5, 3), (2, 4)] {
+            a.connect(x, y);
+        }
+        for &(x, y) in &[(4, 2), (3, 0), (0, 5)] {
+            b.connect(x, y);
+        }
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        b.connect(1, 2);
+        assert_ne!(a.canonical_hash(), b.canonical_hash());
+    }
+}
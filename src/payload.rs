@@ -0,0 +1,149 @@
+use graph::Graph;
+
+/// Weighted union-find with path compression where every component carries a
+/// payload (such as tags, owners or bounding boxes), merged by the given
+/// callback whenever two components join.
+/// 
+/// The callback receives the payload of the larger component first, so that
+/// merging collections by extending the first with the second moves every
+/// element a logarithmic number of times overall (small-to-large).
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::PayloadGraph::new(vec![vec!['a'], vec!['b'], vec!['c']]);
+/// let merge = |mut large: Vec<char>, small: Vec<char>| {
+///     large.extend(small);
+///     large
+/// };
+/// graph.connect(0, 1, merge);
+/// graph.connect(2, 0, merge);
+/// let mut names = graph.payload(1).clone();
+/// names.sort();
+/// assert_eq!(vec!['a', 'b', 'c'], names);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PayloadGraph<T> {
+    graph: Graph,               // components of the nodes
+    payloads: Vec<Option<T>>    // payload of the component of each root
+}
+
+impl<T> PayloadGraph<T> {
+
+    /// Returns a new PayloadGraph with a node for each of the given payloads.
+    /// 
+    /// # Arguments
+    /// * `payloads` The payload of each node.
+    pub fn new(payloads: Vec<T>) -> PayloadGraph<T> {
+        PayloadGraph {
+            graph: Graph::new(payloads.len()),
+            payloads: payloads.into_iter().map(Some).collect()
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.graph.count()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.graph.component_count()
+    }
+
+    /// Returns the payload of the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn payload(&mut self, id: usize) -> &T {
+        let root = self.graph.find(id);
+        // every root holds the payload of its component
+        self.payloads[root].as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the payload of the component the given
+    /// node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn payload_mut(&mut self, id: usize) -> &mut T {
+        let root = self.graph.find(id);
+        self.payloads[root].as_mut().unwrap()
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> usize {
+        self.graph.find(id)
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.graph.connected(a, b)
+    }
+
+    /// Connects the two given nodes, merging the payloads of their components
+    /// with the given callback, which receives the payload of the larger
+    /// component first (or of the component of `a`, if of equal size).
+    /// 
+    /// Returns true only if the two nodes belonged to different components,
+    /// otherwise the callback is not invoked.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// * `merge` Merges the payloads of the two components.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PayloadGraph::new(vec![1, 2, 3]);
+    /// assert!(graph.connect(0, 1, |a, b| a * b));
+    /// assert!(!graph.connect(1, 0, |a, b| a * b));
+    /// assert_eq!(&2, graph.payload(0));
+    /// ```
+    pub fn connect<F: FnOnce(T, T) -> T>(&mut self, a: usize, b: usize, merge: F) -> bool {
+        let a_root = self.graph.find(a);
+        let b_root = self.graph.find(b);
+        match self.graph.connect(a_root, b_root) {
+            Some(root) => {
+                let child = if root == a_root { b_root } else { a_root };
+                let large = self.payloads[root].take().unwrap();
+                let small = self.payloads[child].take().unwrap();
+                self.payloads[root] = Some(merge(large, small));
+                true
+            }
+            None => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_merge_small_into_large() {
+        let mut graph = PayloadGraph::new((0..6).map(|id| vec![id]).collect());
+        let merge = |mut large: Vec<usize>, small: Vec<usize>| {
+            assert!(large.len() >= small.len());
+            large.extend(small);
+            large
+        };
+        graph.connect(0, 1, merge);
+        graph.connect(2, 3, merge);
+        graph.connect(4, 2, merge);
+        graph.connect(1, 3, merge);
+        assert_eq!(2, graph.component_count());
+        graph.payload_mut(5).push(10);
+        assert_eq!(&vec![5, 10], graph.payload(5));
+        let mut ids = graph.payload(0).clone();
+        ids.sort();
+        assert_eq!(vec![0, 1, 2, 3, 4], ids);
+    }
+}
@@ -0,0 +1,406 @@
+//! Percolation on a square grid of sites, and cluster analysis of lattices.
+
+use std::collections::BTreeMap;
+
+use graph::Graph;
+use grid;
+use rng::{self, SplitMix64};
+
+/// Model of an `n`-by-`n` grid of sites, initially blocked, that percolates
+/// when a path of open sites connects the top row to the bottom row.
+/// 
+/// A site is full when it is connected to the top row. Sites are connected
+/// through a virtual top node (joined to every open site of the top row) and
+/// a virtual bottom node (joined to every open site of the bottom row), while
+/// a second graph without the bottom node answers `is_full`, so that sites
+/// connected only to the bottom are never reported as full after the grid
+/// percolates (backwash).
+/// 
+/// # Example
+/// ```
+/// let mut percolation = wuf::percolation::Percolation::new(3);
+/// percolation.open(0, 1);
+/// percolation.open(1, 1);
+/// assert!(percolation.is_full(1, 1));
+/// assert!(!percolation.percolates());
+/// percolation.open(2, 1);
+/// assert!(percolation.percolates());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Percolation {
+    n: usize,           // number of rows (and columns)
+    open: Vec<bool>,    // whether each site is open
+    open_count: usize,  // number of open sites
+    graph: Graph,       // sites and virtual top and bottom nodes
+    full: Graph         // sites and virtual top node only
+}
+
+impl Percolation {
+
+    /// Returns a new grid of `n`-by-`n` blocked sites.
+    /// 
+    /// # Arguments
+    /// * `n` Number of rows and columns of the grid.
+    pub fn new(n: usize) -> Percolation {
+        let sites = n.checked_mul(n).expect("grid too large");
+        Percolation {
+            n,
+            open: vec![false; sites],
+            open_count: 0,
+            graph: Graph::new(sites + 2),
+            full: Graph::new(sites + 1)
+        }
+    }
+
+    /// Returns the number of rows (and columns) of the grid.
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of open sites.
+    pub fn open_count(&self) -> usize {
+        self.open_count
+    }
+
+    /// Opens the given site, if not already open, and connects it to every
+    /// open neighbor.
+    /// 
+    /// Panics if the site is out of bounds.
+    /// 
+    /// # Arguments
+    /// * `row` Row of the site, from 0 (top) to `n - 1` (bottom).
+    /// * `col` Column of the site, from 0 to `n - 1`.
+    pub fn open(&mut self, row: usize, col: usize) {
+        let site = self.site(row, col);
+        if self.open[site] {
+            return;
+        }
+        self.open[site] = true;
+        self.open_count += 1;
+        let (top, bottom) = (self.n * self.n, self.n * self.n + 1);
+        if row == 0 {
+            self.graph.connect(site, top);
+            self.full.connect(site, top);
+        }
+        if row == self.n - 1 {
+            self.graph.connect(site, bottom);
+        }
+        let neighbors = [
+            (row > 0, site.wrapping_sub(self.n)),
+            (row + 1 < self.n, site + self.n),
+            (col > 0, site.wrapping_sub(1)),
+            (col + 1 < self.n, site + 1)
+        ];
+        for &(exists, neighbor) in &neighbors {
+            if exists && self.open[neighbor] {
+                self.graph.connect(site, neighbor);
+                self.full.connect(site, neighbor);
+            }
+        }
+    }
+
+    /// Returns true only if the given site is open.
+    /// 
+    /// Panics if the site is out of bounds.
+    /// 
+    /// # Arguments
+    /// * `row` Row of the site.
+    /// * `col` Column of the site.
+    pub fn is_open(&self, row: usize, col: usize) -> bool {
+        self.open[self.site(row, col)]
+    }
+
+    /// Returns true only if the given site is open and connected to the top
+    /// row through open sites.
+    /// 
+    /// Panics if the site is out of bounds.
+    /// 
+    /// # Arguments
+    /// * `row` Row of the site.
+    /// * `col` Column of the site.
+    pub fn is_full(&self, row: usize, col: usize) -> bool {
+        let site = self.site(row, col);
+        self.full.connected_ref(site, self.n * self.n)
+    }
+
+    /// Returns true only if the top row is connected to the bottom row
+    /// through open sites.
+    pub fn percolates(&self) -> bool {
+        self.n > 0 && self.graph.connected_ref(self.n * self.n, self.n * self.n + 1)
+    }
+
+    /// Returns the index of the given site, panicking if out of bounds.
+    fn site(&self, row: usize, col: usize) -> usize {
+        assert!(
+            row < self.n && col < self.n,
+            "site ({}, {}) out of bounds (size is {})", row, col, self.n
+        );
+        row * self.n + col
+    }
+}
+
+/// Statistics of the percolation threshold (the fraction of open sites when
+/// the grid percolates), estimated by Monte Carlo simulation.
+/// 
+/// Every trial opens the sites of a blocked grid in a uniformly random order,
+/// until the grid percolates.
+/// 
+/// # Example
+/// ```
+/// let stats = wuf::percolation::PercolationStats::with_seed(20, 30, 42);
+/// assert!((stats.mean() - 0.593).abs() < 0.05);
+/// assert!(stats.confidence_low() < stats.mean());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercolationStats {
+    thresholds: Vec<f64>,   // percolation threshold of every trial
+    mean: f64,              // sample mean of the thresholds
+    stddev: f64             // sample standard deviation of the thresholds
+}
+
+impl PercolationStats {
+
+    /// Runs the given number of trials on a grid of `n`-by-`n` sites, with a
+    /// random seed.
+    /// 
+    /// Panics if `n` or `trials` is 0.
+    /// 
+    /// # Arguments
+    /// * `n` Number of rows and columns of the grid.
+    /// * `trials` Number of independent trials.
+    pub fn new(n: usize, trials: usize) -> PercolationStats {
+        PercolationStats::with_seed(n, trials, rng::random_seed())
+    }
+
+    /// Runs the given number of trials on a grid of `n`-by-`n` sites, where
+    /// the same seed always produces the same statistics.
+    /// 
+    /// Panics if `n` or `trials` is 0.
+    /// 
+    /// # Arguments
+    /// * `n` Number of rows and columns of the grid.
+    /// * `trials` Number of independent trials.
+    /// * `seed` Seed of the pseudorandom order in which sites are opened.
+    pub fn with_seed(n: usize, trials: usize, seed: u64) -> PercolationStats {
+        assert!(n > 0 && trials > 0, "expected a non-empty grid and at least one trial");
+        let mut rng = SplitMix64::new(seed);
+        let mut sites: Vec<usize> = (0..n * n).collect();
+        let thresholds: Vec<f64> = (0..trials).map(|_| {
+            rng.shuffle(&mut sites);
+            let mut percolation = Percolation::new(n);
+            for &site in &sites {
+                percolation.open(site / n, site % n);
+                if percolation.percolates() {
+                    break;
+                }
+            }
+            percolation.open_count() as f64 / (n * n) as f64
+        }).collect();
+        let mean = thresholds.iter().sum::<f64>() / trials as f64;
+        let variance = thresholds.iter().map(|t| (t - mean) * (t - mean)).sum::<f64>()
+            / (trials as f64 - 1.0);
+        PercolationStats { thresholds, mean, stddev: variance.sqrt() }
+    }
+
+    /// Returns the percolation threshold of every trial.
+    pub fn thresholds(&self) -> &[f64] {
+        &self.thresholds
+    }
+
+    /// Returns the sample mean of the percolation threshold.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the sample standard deviation of the percolation threshold,
+    /// which is `NaN` for a single trial.
+    pub fn stddev(&self) -> f64 {
+        self.stddev
+    }
+
+    /// Returns the low endpoint of the 95% confidence interval of the mean.
+    pub fn confidence_low(&self) -> f64 {
+        self.mean - self.margin()
+    }
+
+    /// Returns the high endpoint of the 95% confidence interval of the mean.
+    pub fn confidence_high(&self) -> f64 {
+        self.mean + self.margin()
+    }
+
+    /// Returns the half width of the 95% confidence interval of the mean.
+    fn margin(&self) -> f64 {
+        1.96 * self.stddev / (self.thresholds.len() as f64).sqrt()
+    }
+}
+
+/// Boundary conditions of a lattice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// Sites on opposite edges are not adjacent.
+    #[default]
+    Open,
+    /// The lattice wraps around, so that sites on opposite edges (in both
+    /// directions) are adjacent, as on a torus.
+    Periodic
+}
+
+/// Clusters of occupied sites of a lattice, labeled by `hoshen_kopelman`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clusters {
+    labels: Vec<usize>, // label of every site, 0 if not occupied
+    sizes: Vec<usize>   // number of sites of every cluster, by label - 1
+}
+
+impl Clusters {
+
+    /// Returns the label of every site: 0 for empty sites, and from 1 (in
+    /// order of first site) for the clusters of occupied sites.
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Returns the number of clusters.
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns the number of sites of every cluster, where the size of the
+    /// cluster with label `l` is at index `l - 1`.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Returns the number of sites of the largest cluster, or 0 if there are
+    /// no clusters.
+    pub fn largest(&self) -> usize {
+        self.sizes.iter().cloned().max().unwrap_or(0)
+    }
+
+    /// Returns the cluster-size distribution: the number of clusters of every
+    /// size, in ascending order of size.
+    /// 
+    /// # Example
+    /// ```
+    /// use wuf::percolation::{hoshen_kopelman, Boundary};
+    /// 
+    /// let sites = [true, false, true, true, false, false, false, true, true];
+    /// let clusters = hoshen_kopelman(3, 3, &sites, Boundary::Open);
+    /// let distribution: Vec<_> = clusters.size_distribution().into_iter().collect();
+    /// assert_eq!(vec![(1, 1), (2, 2)], distribution);
+    /// ```
+    pub fn size_distribution(&self) -> BTreeMap<usize, usize> {
+        let mut distribution = BTreeMap::new();
+        for &size in &self.sizes {
+            *distribution.entry(size).or_insert(0) += 1;
+        }
+        distribution
+    }
+}
+
+/// Labels the clusters of occupied sites of the given `width`-by-`height`
+/// lattice (in row-major order), where orthogonally adjacent occupied sites
+/// belong to the same cluster, following Hoshen–Kopelman.
+/// 
+/// Sites are visited in raster order and joined to their occupied neighbors
+/// on the left and above, and, with periodic boundaries, the last column and
+/// the last row are also joined to the first column and the first row.
+/// 
+/// Panics if the number of sites is not `width * height`.
+/// 
+/// # Arguments
+/// * `width` Number of columns of the lattice.
+/// * `height` Number of rows of the lattice.
+/// * `sites` Whether each site is occupied.
+/// * `boundary` Boundary conditions of the lattice.
+/// 
+/// # Example
+/// ```
+/// use wuf::percolation::{hoshen_kopelman, Boundary};
+/// 
+/// let sites = [true, false, true];
+/// assert_eq!(2, hoshen_kopelman(3, 1, &sites, Boundary::Open).count());
+/// assert_eq!(vec![2], hoshen_kopelman(3, 1, &sites, Boundary::Periodic).sizes());
+/// ```
+pub fn hoshen_kopelman(width: usize, height: usize, sites: &[bool], boundary: Boundary) -> Clusters {
+    assert_eq!(
+        width.checked_mul(height), Some(sites.len()),
+        "expected {} by {} sites", width, height
+    );
+    let mut graph = Graph::new(sites.len());
+    let periodic = boundary == Boundary::Periodic;
+    for y in 0..height {
+        for x in (0..width).filter(|&x| sites[y * width + x]) {
+            let site = y * width + x;
+            let left = if x > 0 { Some(site - 1) } else { None };
+            let up = if y > 0 { Some(site - width) } else { None };
+            // wrap around to the first column and to the first row
+            let right = if periodic && x + 1 == width { Some(y * width) } else { None };
+            let down = if periodic && y + 1 == height { Some(x) } else { None };
+            for neighbor in [left, up, right, down].iter().flatten().cloned() {
+                if sites[neighbor] {
+                    graph.connect(site, neighbor);
+                }
+            }
+        }
+    }
+    let labels = grid::labels(&mut graph, sites);
+    let mut sizes = Vec::new();
+    for &label in labels.iter().filter(|&&label| label > 0) {
+        if label > sizes.len() {
+            sizes.push(0);
+        }
+        sizes[label - 1] += 1;
+    }
+    Clusters { labels, sizes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_not_backwash() {
+        let mut percolation = Percolation::new(3);
+        for &(row, col) in &[(0, 0), (1, 0), (2, 0), (2, 2)] {
+            percolation.open(row, col);
+        }
+        percolation.open(2, 2);
+        assert_eq!(4, percolation.open_count());
+        assert!(percolation.percolates());
+        assert!(percolation.is_full(2, 0));
+        // connected to the bottom row only
+        assert!(percolation.is_open(2, 2));
+        assert!(!percolation.is_full(2, 2));
+        assert!(!percolation.is_full(1, 1));
+        assert!(!Percolation::new(0).percolates());
+    }
+
+    #[test]
+    fn should_wrap_clusters() {
+        let sites: Vec<bool> = [
+            "#..#",
+            "....",
+            "#.##",
+        ].iter().flat_map(|row| row.chars().map(|c| c == '#')).collect();
+        let open = hoshen_kopelman(4, 3, &sites, Boundary::Open);
+        assert_eq!(&[1, 0, 0, 2, 0, 0, 0, 0, 3, 0, 4, 4], open.labels());
+        assert_eq!(&[1, 1, 1, 2], open.sizes());
+        let periodic = hoshen_kopelman(4, 3, &sites, Boundary::Periodic);
+        // the four corners and the bottom row form a single cluster
+        assert_eq!(vec![5], periodic.sizes());
+        assert_eq!(5, periodic.largest());
+        assert_eq!(0, hoshen_kopelman(2, 1, &[false, false], Boundary::Periodic).largest());
+    }
+
+    #[test]
+    fn should_reproduce_stats() {
+        let stats = PercolationStats::with_seed(10, 50, 7);
+        assert_eq!(stats, PercolationStats::with_seed(10, 50, 7));
+        assert_ne!(stats, PercolationStats::with_seed(10, 50, 8));
+        assert!(stats.thresholds().iter().all(|&t| t > 0.0 && t <= 1.0));
+        assert!(stats.confidence_low() < stats.mean() && stats.mean() < stats.confidence_high());
+        assert_eq!(1.0, PercolationStats::with_seed(1, 1, 0).mean());
+        assert!(PercolationStats::with_seed(1, 1, 0).stddev().is_nan());
+    }
+}
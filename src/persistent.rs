@@ -0,0 +1,259 @@
+use union_find::UnionFind;
+
+// link time of the roots, that are not linked to any parent
+const NEVER: usize = usize::MAX;
+
+/// Partially persistent union-find: every successful union creates a new
+/// version of the graph, and connectivity can be queried as of any version,
+/// while only the latest version can be modified.
+/// 
+/// Nodes are linked by rank without path compression, and every node records
+/// the version in which it was linked to its parent, so that the root of a
+/// node as of a version is found by following only the links created up to
+/// that version, in time logarithmic in the number of nodes.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::PersistentGraph::new(3);
+/// let v1 = graph.connect(0, 1).unwrap();
+/// let v2 = graph.connect(1, 2).unwrap();
+/// assert!(graph.connected_at(0, 1, v1));
+/// assert!(!graph.connected_at(0, 2, v1));
+/// assert!(graph.connected_at(0, 2, v2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistentGraph {
+    parents: Vec<usize>,    // parent of each node, roots are their own parent
+    ranks: Vec<u8>,         // upper bound on the height of each tree
+    times: Vec<usize>,      // version in which each node was linked to its parent
+    version: usize          // latest version, that is the number of unions
+}
+
+impl PersistentGraph {
+
+    /// Returns a new PersistentGraph with the given number of nodes, at
+    /// version 0.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::PersistentGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// assert_eq!(0, graph.version());
+    /// ```
+    pub fn new(n: usize) -> PersistentGraph {
+        PersistentGraph {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            times: vec![NEVER; n],
+            version: 0
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the latest version, which is also the number of successful
+    /// unions.
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// Returns the number of disjoint components in the latest version, in
+    /// constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PersistentGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.parents.len() - self.version
+    }
+
+    /// Returns the root of the given node in the latest version.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&self, id: usize) -> usize {
+        self.find_at(id, self.version)
+    }
+
+    /// Returns the root of the given node as of the given version, where
+    /// versions greater than the latest one are treated as the latest one.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// * `version` Version of the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PersistentGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(1, graph.find_at(1, 0));
+    /// assert_eq!(graph.find(0), graph.find_at(1, 1));
+    /// ```
+    pub fn find_at(&self, id: usize, version: usize) -> usize {
+        let mut node = id;
+        // links are created in ascending order of version along the path
+        while self.times[node] <= version {
+            node = self.parents[node];
+        }
+        node
+    }
+
+    /// Returns true only if the two given nodes are connected in the latest
+    /// version.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns true only if the two given nodes were connected as of the
+    /// given version.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// * `version` Version of the graph.
+    pub fn connected_at(&self, a: usize, b: usize, version: usize) -> bool {
+        self.find_at(a, version) == self.find_at(b, version)
+    }
+
+    /// Returns the earliest version as of which the two given nodes are
+    /// connected, or `None` if they are not connected in the latest version.
+    /// 
+    /// The version is the greatest link version along the paths from the two
+    /// nodes to their lowest common ancestor, found in time logarithmic in the
+    /// number of nodes.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PersistentGraph::new(4);
+    /// graph.connect(0, 1);
+    /// graph.connect(2, 3);
+    /// graph.connect(1, 2);
+    /// assert_eq!(Some(1), graph.connected_since(1, 0));
+    /// assert_eq!(Some(3), graph.connected_since(0, 3));
+    /// assert_eq!(Some(0), graph.connected_since(2, 2));
+    /// ```
+    pub fn connected_since(&self, a: usize, b: usize) -> Option<usize> {
+        if !self.connected(a, b) {
+            return None;
+        }
+        let (mut a, mut b) = (a, b);
+        let mut since = 0;
+        // climb from the node linked earliest, until the paths meet
+        while a != b {
+            if self.times[a] < self.times[b] {
+                since = self.times[a];
+                a = self.parents[a];
+            } else {
+                since = self.times[b];
+                b = self.parents[b];
+            }
+        }
+        Some(since)
+    }
+
+    /// Connects the two given nodes in a new version of the graph.
+    /// 
+    /// Returns the new version if the two nodes belonged to different
+    /// components, otherwise returns `None` (and no version is created).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::PersistentGraph::new(10);
+    /// assert_eq!(Some(1), graph.connect(0, 1));
+    /// assert_eq!(None, graph.connect(1, 0));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let (mut root, mut child) = (self.find(a), self.find(b));
+        if root == child {
+            return None;
+        }
+        if self.ranks[root] < self.ranks[child] {
+            ::std::mem::swap(&mut root, &mut child);
+        }
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.version += 1;
+        self.parents[child] = root;
+        self.times[child] = self.version;
+        Some(self.version)
+    }
+}
+
+impl UnionFind for PersistentGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        PersistentGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        PersistentGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::Graph;
+
+    #[test]
+    fn should_query_every_version() {
+        let n = 30;
+        let edges: Vec<(usize, usize)> = (0..60u64).map(|i| {
+            let hash = ::rng::mix(i);
+            ((hash % n) as usize, ((hash >> 8) % n) as usize)
+        }).collect();
+        let mut graph = PersistentGraph::new(n as usize);
+        let mut snapshots = vec![Graph::new(n as usize)];
+        for &(a, b) in &edges {
+            if graph.connect(a, b).is_some() {
+                let mut snapshot = snapshots.last().unwrap().clone();
+                snapshot.connect(a, b);
+                snapshots.push(snapshot);
+            }
+        }
+        assert_eq!(snapshots.len() - 1, graph.version());
+        for (version, snapshot) in snapshots.iter().enumerate() {
+            for a in 0..n as usize {
+                for b in 0..n as usize {
+                    assert_eq!(snapshot.connected_ref(a, b), graph.connected_at(a, b, version));
+                }
+            }
+        }
+        // the earliest version connecting two nodes is the first snapshot doing so
+        for a in 0..n as usize {
+            for b in 0..n as usize {
+                let since = snapshots.iter().position(|snapshot| snapshot.connected_ref(a, b));
+                assert_eq!(since, graph.connected_since(a, b));
+            }
+        }
+    }
+}
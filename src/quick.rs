@@ -0,0 +1,244 @@
+use union_find::UnionFind;
+
+/// Quick-find union-find: every node stores the ID of its component, so that
+/// `find` is constant time while `connect` is linear in the number of nodes.
+/// 
+/// Mostly useful as a baseline, or when queries vastly outnumber unions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickFind {
+    nodes: Vec<usize>,  // component of each node
+    components: usize   // number of disjoint components
+}
+
+impl QuickFind {
+
+    /// Returns a new QuickFind with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::QuickFind::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> QuickFind {
+        QuickFind { nodes: (0..n).collect(), components: n }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the component of the given node, in constant time.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::QuickFind::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        self.nodes[id]
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.nodes[a] == self.nodes[b]
+    }
+
+    /// Connects the two given nodes, returning true only if they belonged to
+    /// different components.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::QuickFind::new(10);
+    /// assert!(graph.connect(0, 1));
+    /// assert!(!graph.connect(1, 0));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> bool {
+        let a_id = self.nodes[a];
+        let b_id = self.nodes[b];
+        if a_id == b_id {
+            return false;
+        }
+        // relabel every node of the component of a
+        for id in self.nodes.iter_mut().filter(|id| **id == a_id) {
+            *id = b_id;
+        }
+        self.components -= 1;
+        true
+    }
+}
+
+impl UnionFind for QuickFind {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        QuickFind::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b)
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        QuickFind::connected(self, a, b)
+    }
+}
+
+/// Quick-union union-find: every node stores its parent and roots are linked
+/// without any balancing or path compression, so that trees can degenerate
+/// into linear paths.
+/// 
+/// Mostly useful as a baseline to compare against `Graph`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickUnion {
+    nodes: Vec<usize>,  // parent of each node
+    components: usize   // number of disjoint components
+}
+
+impl QuickUnion {
+
+    /// Returns a new QuickUnion with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::QuickUnion::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> QuickUnion {
+        QuickUnion { nodes: (0..n).collect(), components: n }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::QuickUnion::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(1, graph.find(0));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        let mut root = id;
+        while root != self.nodes[root] {
+            root = self.nodes[root];
+        }
+        root
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes by linking the root of `a` below the root
+    /// of `b`, returning true only if they belonged to different components.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::QuickUnion::new(10);
+    /// assert!(graph.connect(0, 1));
+    /// assert!(!graph.connect(1, 0));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> bool {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return false;
+        }
+        self.nodes[a_root] = b_root;
+        self.components -= 1;
+        true
+    }
+}
+
+impl UnionFind for QuickUnion {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        QuickUnion::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b)
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        QuickUnion::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_quick_find() {
+        let mut graph = QuickFind::new(5);
+        assert!(graph.connect(0, 1));
+        assert!(graph.connect(2, 1));
+        assert!(!graph.connect(0, 2));
+        assert_eq!(3, graph.component_count());
+        assert_eq!(graph.find(0), graph.find(2));
+        assert!(!graph.connected(0, 3));
+    }
+
+    #[test]
+    fn should_quick_union() {
+        let mut graph = QuickUnion::new(5);
+        assert!(graph.connect(0, 1));
+        assert!(graph.connect(1, 2));
+        assert!(!graph.connect(0, 2));
+        assert_eq!(3, graph.component_count());
+        assert_eq!(2, graph.find(0));
+        assert!(!graph.connected(0, 3));
+    }
+}
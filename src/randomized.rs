@@ -0,0 +1,194 @@
+use rng;
+use union_find::UnionFind;
+
+/// Union-find linking by randomized priorities.
+/// 
+/// Every node is assigned a pseudo-random priority at construction, and the
+/// root with lower priority is always linked below the root with higher
+/// priority. Priorities are derived from the node ID and a seed rather than
+/// stored, so that no array other than the parents is needed, while trees
+/// still have an expected logarithmic depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomizedGraph {
+    nodes: Vec<usize>,  // parent of each node
+    seed: u64,          // seed the priorities are derived from
+    components: usize   // number of disjoint components
+}
+
+impl RandomizedGraph {
+
+    /// Returns a new RandomizedGraph with the given number of nodes, whose
+    /// priorities are derived from a random seed.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::RandomizedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> RandomizedGraph {
+        RandomizedGraph::with_seed(n, rng::random_seed())
+    }
+
+    /// Returns a new RandomizedGraph with the given number of nodes, whose
+    /// priorities are derived from the given seed, so that the shape of the
+    /// forest is reproducible.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `seed` Seed the priorities are derived from.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph1 = wuf::RandomizedGraph::with_seed(10, 42);
+    /// let mut graph2 = wuf::RandomizedGraph::with_seed(10, 42);
+    /// assert_eq!(graph1.connect(0, 1), graph2.connect(0, 1));
+    /// ```
+    pub fn with_seed(n: usize, seed: u64) -> RandomizedGraph {
+        RandomizedGraph { nodes: (0..n).collect(), seed, components: n }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::RandomizedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RandomizedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the priority of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RandomizedGraph::new(10);
+    /// let root = graph.connect(0, 1).unwrap();
+    /// assert!(graph.priority(root) >= graph.priority(0));
+    /// assert!(graph.priority(root) >= graph.priority(1));
+    /// ```
+    pub fn priority(&self, id: usize) -> u64 {
+        rng::mix(self.seed ^ id as u64)
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RandomizedGraph::new(10);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RandomizedGraph::new(10);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        // link the root of lower priority below the root of higher priority,
+        // breaking ties by ID
+        let (root, child) = if (self.priority(a_root), a_root) < (self.priority(b_root), b_root) {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.nodes[child] = root;
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RandomizedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        let mut node = id;
+        while node != self.nodes[node] {
+            // make every other node in path point to its grandparent
+            let grandparent = self.nodes[self.nodes[node]];
+            self.nodes[node] = grandparent;
+            node = grandparent;
+        }
+        node
+    }
+}
+
+impl UnionFind for RandomizedGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        RandomizedGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_link_by_priority() {
+        let mut graph = RandomizedGraph::with_seed(100, 7);
+        for id in 1..100 {
+            graph.connect(id - 1, id);
+        }
+        assert_eq!(1, graph.component_count());
+        // the root is the node of highest priority
+        let root = graph.find(0);
+        assert!((0..100).all(|id| graph.priority(id) <= graph.priority(root)));
+        assert!((0..100).all(|id| graph.find(id) == root));
+    }
+}
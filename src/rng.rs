@@ -0,0 +1,56 @@
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hasher};
+
+/// Scrambles the bits of the given value (SplitMix64 finalizer), such that
+/// close inputs produce unrelated outputs.
+pub(crate) fn mix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Returns a seed that changes on every call, taken from the randomness the
+/// standard library uses to protect hash maps.
+#[cfg(feature = "std")]
+pub(crate) fn random_seed() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// SplitMix64 pseudorandom generator: fast, seedable and with a period of
+/// 2^64, but not suitable for cryptography.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64
+}
+
+#[cfg(feature = "std")]
+impl SplitMix64 {
+    /// Returns a new generator with the given seed.
+    pub(crate) fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    /// Returns the next pseudorandom number.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.state)
+    }
+
+    /// Returns a pseudorandom number lower than the given (non-zero) bound.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        // multiply and shift, whose bias is negligible for the bounds used
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+
+    /// Shuffles the given slice in place (Fisher–Yates).
+    pub(crate) fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.below(i + 1);
+            values.swap(i, j);
+        }
+    }
+}
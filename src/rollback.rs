@@ -0,0 +1,286 @@
+use union_find::UnionFind;
+
+/// Union-find linking by rank without path compression, that records every
+/// successful union so that the latest ones can be undone.
+/// 
+/// Since paths are never compressed, `find` takes time logarithmic in the
+/// number of nodes, while `undo` takes constant time per union, which makes
+/// this graph suitable for backtracking search and for offline algorithms
+/// that visit states depth-first (see `dynamic::solve`).
+/// 
+/// Unions can also be grouped into nested scopes, as the assertion levels of
+/// SMT solvers: `push` opens a scope, and `pop` undoes every union made since
+/// the matching `push`.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::RollbackGraph::new(4);
+/// graph.connect(0, 1);
+/// graph.connect(2, 3);
+/// graph.connect(1, 2);
+/// graph.undo(2);
+/// assert!(graph.connected(0, 1));
+/// assert!(!graph.connected(2, 3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackGraph {
+    parents: Vec<usize>,            // parent of each node, roots are their own parent
+    ranks: Vec<u8>,                 // upper bound on the height of each tree
+    history: Vec<(usize, bool)>,    // linked root, and whether its parent's rank grew
+    scopes: Vec<usize>,             // number of unions when each scope was opened
+    components: usize               // number of disjoint components
+}
+
+impl RollbackGraph {
+
+    /// Returns a new RollbackGraph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::RollbackGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> RollbackGraph {
+        RollbackGraph {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            history: Vec::new(),
+            scopes: Vec::new(),
+            components: n
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the number of successful unions that can be undone.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.connect(1, 0);
+    /// assert_eq!(1, graph.unions());
+    /// ```
+    pub fn unions(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the root of the given node, in time logarithmic in the number
+    /// of nodes.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&self, id: usize) -> usize {
+        let mut node = id;
+        while self.parents[node] != node {
+            node = self.parents[node];
+        }
+        node
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes, returning true only if they belonged to
+    /// different components, in which case the union is recorded.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// assert!(graph.connect(0, 1));
+    /// assert!(!graph.connect(1, 0));
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> bool {
+        let (mut root, mut child) = (self.find(a), self.find(b));
+        if root == child {
+            return false;
+        }
+        if self.ranks[root] < self.ranks[child] {
+            ::std::mem::swap(&mut root, &mut child);
+        }
+        let grown = self.ranks[root] == self.ranks[child];
+        if grown {
+            self.ranks[root] += 1;
+        }
+        self.parents[child] = root;
+        self.history.push((child, grown));
+        self.components -= 1;
+        true
+    }
+
+    /// Undoes the last `k` successful unions, in time linear in `k`.
+    /// 
+    /// Panics if `k` is greater than the number of unions (see `unions`)
+    /// made since the innermost scope was opened.
+    /// 
+    /// # Arguments
+    /// * `k` Number of unions to undo.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.undo(1);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn undo(&mut self, k: usize) {
+        let start = self.scopes.last().cloned().unwrap_or(0);
+        assert!(
+            k <= self.history.len() - start,
+            "cannot undo {} unions out of {}", k, self.history.len() - start
+        );
+        for _ in 0..k {
+            // the history is not empty since it was checked above
+            let (child, grown) = self.history.pop().unwrap();
+            let root = self.parents[child];
+            if grown {
+                self.ranks[root] -= 1;
+            }
+            self.parents[child] = child;
+            self.components += 1;
+        }
+    }
+
+    /// Returns the number of open scopes.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Opens a new scope, nested in the current one, whose unions are undone
+    /// by the matching `pop`.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::RollbackGraph::new(10);
+    /// graph.connect(0, 1);
+    /// graph.push();
+    /// graph.connect(1, 2);
+    /// graph.push();
+    /// graph.connect(2, 3);
+    /// graph.pop();
+    /// assert!(graph.connected(0, 2) && !graph.connected(0, 3));
+    /// graph.pop();
+    /// assert!(graph.connected(0, 1) && !graph.connected(0, 2));
+    /// ```
+    pub fn push(&mut self) {
+        self.scopes.push(self.history.len());
+    }
+
+    /// Closes the innermost scope, undoing every union made since it was
+    /// opened.
+    /// 
+    /// Panics if there is no open scope.
+    pub fn pop(&mut self) {
+        let start = self.scopes.pop().expect("no scope to pop");
+        let k = self.history.len() - start;
+        self.undo(k);
+    }
+}
+
+impl UnionFind for RollbackGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        RollbackGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b)
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        RollbackGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_restore_previous_state() {
+        let mut graph = RollbackGraph::new(8);
+        graph.connect(0, 1);
+        graph.connect(2, 3);
+        let snapshot = graph.clone();
+        graph.connect(1, 3);
+        graph.connect(3, 0);
+        graph.connect(4, 5);
+        graph.connect(5, 1);
+        assert_eq!(3, graph.component_count());
+        // the failed union of 3 and 0 is not recorded
+        assert_eq!(5, graph.unions());
+        graph.undo(3);
+        assert_eq!(snapshot, graph);
+        graph.undo(2);
+        assert_eq!(RollbackGraph::new(8), graph);
+    }
+
+    #[test]
+    fn should_pop_scopes() {
+        let mut graph = RollbackGraph::new(6);
+        graph.connect(0, 1);
+        let outer = graph.clone();
+        graph.push();
+        graph.connect(1, 2);
+        graph.push();
+        let inner = graph.clone();
+        graph.push();
+        graph.connect(3, 4);
+        graph.connect(4, 0);
+        assert_eq!(3, graph.depth());
+        graph.pop();
+        graph.pop();
+        assert_eq!(inner.parents, graph.parents);
+        assert_eq!(1, graph.depth());
+        graph.pop();
+        assert_eq!(outer, graph);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot undo 1 unions out of 0")]
+    fn should_not_undo_outside_scope() {
+        let mut graph = RollbackGraph::new(2);
+        graph.connect(0, 1);
+        graph.push();
+        graph.undo(1);
+    }
+}
@@ -0,0 +1,93 @@
+use std::vec::Vec;
+
+use serde::de::Error;
+use serde::ser;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use graph::{Compression, Graph, Linking};
+use index::Index;
+
+/// Serialized form of a graph, independent of the index type and of the
+/// internal encoding of the sizes.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Graph")]
+struct GraphRepr {
+    parents: Vec<usize>,        // parent of each node (roots are their own parent)
+    sizes: Vec<usize>,          // size of the tree rooted at each node (0 for non-roots)
+    #[serde(default)]
+    ranks: Vec<u8>,             // rank of each node, only when linking by rank
+    #[serde(default)]
+    linking: Linking,           // strategy used to link roots
+    #[serde(default)]
+    compression: Compression    // strategy used to compress paths
+}
+
+impl<I: Index> Serialize for Graph<I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_not_retired().map_err(ser::Error::custom)?;
+        let (parents, sizes) = self.parents_and_sizes();
+        GraphRepr {
+            parents,
+            sizes,
+            ranks: self.ranks().to_vec(),
+            linking: self.linking(),
+            compression: self.compression()
+        }.serialize(serializer)
+    }
+}
+
+impl<'de, I: Index> Deserialize<'de> for Graph<I> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GraphRepr::deserialize(deserializer)?;
+        Graph::from_checked_parts(
+            repr.parents,
+            Some(&repr.sizes),
+            repr.ranks,
+            repr.linking,
+            repr.compression
+        ).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::WufError;
+    use serde_json;
+
+    #[test]
+    fn should_roundtrip() {
+        let mut graph = Graph::<u32>::with_nodes(5);
+        graph.connect(0, 1);
+        graph.connect(2, 1);
+        graph.connect(3, 4);
+        let json = serde_json::to_string(&graph).unwrap();
+        let mut other: Graph<u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, other.component_count());
+        assert_eq!(3, other.component_size(2));
+        assert!(other.connected(0, 2));
+        assert!(!other.connected(0, 3));
+        // the forest is preserved exactly
+        assert_eq!(graph.parents_and_sizes(), other.parents_and_sizes());
+        // retired nodes cannot be serialized
+        other.remove_node(4);
+        let err = serde_json::to_string(&other).unwrap_err();
+        assert_eq!(WufError::Retired { id: 4 }.to_string(), err.to_string());
+    }
+
+    #[test]
+    fn should_validate() {
+        let cases = [
+            (r#"{"parents":[0,2],"sizes":[1,0]}"#, WufError::InvalidParent { id: 1, parent: 2 }),
+            (r#"{"parents":[1,2,1],"sizes":[0,0,0]}"#, WufError::Cycle { id: 1 }),
+            (r#"{"parents":[0,0],"sizes":[1,0]}"#, WufError::SizeMismatch { id: 0, expected: 2, found: 1 }),
+            (r#"{"parents":[0],"sizes":[1],"ranks":[0]}"#, WufError::LengthMismatch { len: 1, count: 1 }),
+            (r#"{"parents":[0,1],"sizes":[1,1],"ranks":[255,255],"linking":"Rank"}"#, WufError::InvalidRank { id: 0, rank: 255 }),
+            (r#"{"parents":[0,0],"sizes":[2,0],"ranks":[1,1],"linking":"Rank"}"#, WufError::InvalidRank { id: 1, rank: 1 }),
+        ];
+        for &(json, ref err) in &cases {
+            let result = serde_json::from_str::<Graph>(json);
+            assert_eq!(err.to_string(), result.unwrap_err().to_string());
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::ops::Deref;
+use std::sync::Arc;
+
+use graph::Graph;
+use index::Index;
+use union_find::UnionFind;
+
+/// Graph with copy-on-write clones: cloning only shares the underlying graph
+/// in constant time, while its arrays are copied by the first clone that
+/// connects nodes (see `Arc::make_mut`), so that snapshots are cheap.
+/// 
+/// Finding a root of a shared graph does not compress paths, since that
+/// would copy the arrays, hence shared snapshots are best used read-mostly.
+/// The methods of `Graph` taking `&self` are available through `Deref`.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::SharedGraph::new(10);
+/// graph.connect(0, 1);
+/// let snapshot = graph.clone();
+/// graph.connect(1, 2);
+/// assert!(!snapshot.connected_ref(0, 2));
+/// assert!(graph.connected_ref(0, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedGraph<I: Index = usize> {
+    graph: Arc<Graph<I>>    // graph shared with the clones
+}
+
+impl SharedGraph {
+
+    /// Returns a new SharedGraph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::SharedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> SharedGraph {
+        SharedGraph::from(Graph::new(n))
+    }
+}
+
+impl<I: Index> SharedGraph<I> {
+
+    /// Returns true only if the graph is shared with at least one clone, in
+    /// which case the next connection copies the arrays.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::SharedGraph::new(10);
+    /// let snapshot = graph.clone();
+    /// assert!(graph.is_shared());
+    /// drop(snapshot);
+    /// assert!(!graph.is_shared());
+    /// ```
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.graph) > 1
+    }
+
+    /// Returns the root of the given node, compressing its path only if the
+    /// graph is not shared.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SharedGraph::new(10);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        match Arc::get_mut(&mut self.graph) {
+            Some(graph) => graph.find(id),
+            None => self.graph.find_ref(id)
+        }
+    }
+
+    /// Returns true only if the two given nodes are connected, otherwise
+    /// returns false, compressing their paths only if the graph is not
+    /// shared.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SharedGraph::new(10);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes, first copying the arrays if the graph is
+    /// shared and the nodes are not already connected.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SharedGraph::new(10);
+    /// let snapshot = graph.clone();
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(!graph.is_shared());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        if self.is_shared() && self.graph.connected_ref(a, b) {
+            // nothing would change, hence there is no need to copy
+            return None;
+        }
+        Arc::make_mut(&mut self.graph).connect(a, b)
+    }
+
+    /// Returns the underlying graph, copying it only if it is shared.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SharedGraph::new(4);
+    /// graph.connect(0, 1);
+    /// assert_eq!(3, graph.into_graph().component_count());
+    /// ```
+    pub fn into_graph(self) -> Graph<I> {
+        Arc::try_unwrap(self.graph).unwrap_or_else(|graph| (*graph).clone())
+    }
+}
+
+impl<I: Index> From<Graph<I>> for SharedGraph<I> {
+    fn from(graph: Graph<I>) -> SharedGraph<I> {
+        SharedGraph { graph: Arc::new(graph) }
+    }
+}
+
+impl<I: Index> Deref for SharedGraph<I> {
+    type Target = Graph<I>;
+
+    fn deref(&self) -> &Graph<I> {
+        &self.graph
+    }
+}
+
+impl<I: Index> UnionFind for SharedGraph<I> {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        SharedGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        SharedGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_copy_on_write() {
+        let mut graph = SharedGraph::from(Graph::<u16>::with_nodes(6));
+        graph.connect(0, 1);
+        let mut snapshot = graph.clone();
+        assert!(Arc::ptr_eq(&graph.graph, &snapshot.graph));
+        // queries and redundant connections do not copy
+        assert!(snapshot.connected(1, 0));
+        assert!(snapshot.connect(0, 1).is_none());
+        assert!(Arc::ptr_eq(&graph.graph, &snapshot.graph));
+        snapshot.connect(2, 3);
+        assert!(!Arc::ptr_eq(&graph.graph, &snapshot.graph));
+        assert_eq!(5, graph.component_count());
+        assert_eq!(4, snapshot.component_count());
+        assert!(!graph.connected(2, 3));
+        assert!(snapshot.into_graph().connected(2, 3));
+    }
+}
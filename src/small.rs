@@ -0,0 +1,270 @@
+use std::vec::Vec;
+
+use union_find::UnionFind;
+
+/// Parent and size of each node, stored inline up to `N` nodes, and on the
+/// heap beyond.
+#[derive(Debug, Clone)]
+enum Nodes<const N: usize> {
+    Inline([(usize, usize); N], usize),
+    Heap(Vec<(usize, usize)>)
+}
+
+impl<const N: usize> Nodes<N> {
+    fn as_slice(&self) -> &[(usize, usize)] {
+        match *self {
+            Nodes::Inline(ref nodes, len) => &nodes[..len],
+            Nodes::Heap(ref nodes) => nodes
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [(usize, usize)] {
+        match *self {
+            Nodes::Inline(ref mut nodes, len) => &mut nodes[..len],
+            Nodes::Heap(ref mut nodes) => nodes
+        }
+    }
+
+    fn push(&mut self, node: (usize, usize)) {
+        match *self {
+            Nodes::Inline(ref mut nodes, ref mut len) if *len < N => {
+                nodes[*len] = node;
+                *len += 1;
+                return;
+            }
+            Nodes::Inline(ref nodes, _) => {
+                // spill to the heap, doubling the room for the nodes
+                let mut spilled = Vec::with_capacity(2 * N.max(1));
+                spilled.extend_from_slice(nodes);
+                *self = Nodes::Heap(spilled);
+            }
+            Nodes::Heap(_) => {}
+        }
+        if let Nodes::Heap(ref mut nodes) = *self {
+            nodes.push(node);
+        }
+    }
+}
+
+/// Weighted union-find storing up to `N` nodes inline (32 by default), and
+/// spilling them to the heap only when growing beyond, so that creating many
+/// small graphs does not allocate.
+/// 
+/// Roots are linked by size and paths are compressed by halving.
+/// 
+/// # Example
+/// ```
+/// let mut graph = wuf::SmallGraph::<4>::new(4);
+/// graph.connect(0, 1);
+/// assert!(graph.is_inline());
+/// let id = graph.add_node();
+/// graph.connect(id, 0);
+/// assert!(!graph.is_inline());
+/// assert_eq!(3, graph.component_size(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmallGraph<const N: usize = 32> {
+    nodes: Nodes<N>,    // parent and size of each node
+    components: usize   // number of disjoint components
+}
+
+impl<const N: usize> SmallGraph<N> {
+
+    /// Returns a new SmallGraph with the given number of nodes, stored inline
+    /// if they are at most `N`.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph: wuf::SmallGraph = wuf::SmallGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// assert!(graph.is_inline());
+    /// ```
+    pub fn new(n: usize) -> SmallGraph<N> {
+        let nodes = if n <= N {
+            let mut nodes = [(0, 1); N];
+            for (id, node) in nodes.iter_mut().enumerate() {
+                node.0 = id;
+            }
+            Nodes::Inline(nodes, n)
+        } else {
+            Nodes::Heap((0..n).map(|id| (id, 1)).collect())
+        };
+        SmallGraph { nodes, components: n }
+    }
+
+    /// Returns true only if the nodes are stored inline rather than on the
+    /// heap.
+    pub fn is_inline(&self) -> bool {
+        match self.nodes {
+            Nodes::Inline(..) => true,
+            Nodes::Heap(_) => false
+        }
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::SmallGraph::<8>::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.nodes.as_slice().len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SmallGraph::<8>::new(8);
+    /// graph.connect(0, 1);
+    /// assert_eq!(7, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Adds a new node as its own component, spilling the nodes to the heap
+    /// if they no longer fit inline, and returns its ID.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SmallGraph::<8>::new(8);
+    /// assert_eq!(8, graph.add_node());
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn add_node(&mut self) -> usize {
+        let id = self.count();
+        self.nodes.push((id, 1));
+        self.components += 1;
+        id
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn component_size(&mut self, id: usize) -> usize {
+        let root = self.find(id);
+        self.nodes.as_slice()[root].1
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SmallGraph::<8>::new(8);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        let nodes = self.nodes.as_mut_slice();
+        // balance by linking root of smaller tree to root of larger tree
+        let (root, child) = if nodes[a_root].1 < nodes[b_root].1 {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        nodes[child].0 = root;
+        nodes[root].1 += nodes[child].1;
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SmallGraph::<8>::new(8);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        let nodes = self.nodes.as_mut_slice();
+        let mut node = id;
+        while node != nodes[node].0 {
+            // make every other node in path point to its grandparent
+            let grandparent = nodes[nodes[node].0].0;
+            nodes[node].0 = grandparent;
+            node = grandparent;
+        }
+        node
+    }
+}
+
+impl<const N: usize> Default for SmallGraph<N> {
+    fn default() -> SmallGraph<N> {
+        SmallGraph::new(0)
+    }
+}
+
+impl<const N: usize> UnionFind for SmallGraph<N> {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        SmallGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        SmallGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_spill_to_heap() {
+        let mut graph = SmallGraph::<3>::default();
+        for _ in 0..3 {
+            graph.add_node();
+        }
+        graph.connect(0, 2);
+        assert!(graph.is_inline());
+        for _ in 0..3 {
+            graph.add_node();
+        }
+        assert!(!graph.is_inline());
+        graph.connect(5, 2);
+        assert_eq!(4, graph.component_count());
+        assert_eq!(3, graph.component_size(0));
+        assert!(graph.connected(0, 5));
+        assert!(!SmallGraph::<3>::new(4).is_inline());
+        assert_eq!(0, SmallGraph::<0>::new(0).add_node());
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use union_find::UnionFind;
+
+/// Weighted union-find with path compression over a sparse space of 64-bit
+/// node IDs.
+/// 
+/// Nodes are created lazily the first time they are connected, so that the
+/// memory used is proportional to the number of nodes actually touched rather
+/// than to the greatest ID. A node that was never connected is a singleton.
+#[derive(Debug, Clone, Default)]
+pub struct SparseGraph {
+    parents: HashMap<u64, u64>, // parent of each node (roots point to themselves)
+    sizes: HashMap<u64, u64>,   // number of nodes in the tree of each root
+    components: usize           // number of disjoint components
+}
+
+impl SparseGraph {
+
+    /// Returns a new empty SparseGraph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::SparseGraph::new();
+    /// assert_eq!(0, graph.count());
+    /// ```
+    pub fn new() -> SparseGraph {
+        SparseGraph::default()
+    }
+
+    /// Returns a new empty SparseGraph, able to hold at least `capacity` nodes
+    /// without reallocating.
+    /// 
+    /// # Arguments
+    /// * `capacity` Number of nodes the graph can hold without reallocating.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::SparseGraph::with_capacity(100);
+    /// assert_eq!(0, graph.count());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> SparseGraph {
+        SparseGraph {
+            parents: HashMap::with_capacity(capacity),
+            sizes: HashMap::with_capacity(capacity),
+            components: 0
+        }
+    }
+
+    /// Returns the number of nodes touched so far.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// graph.connect(1 << 40, 7);
+    /// assert_eq!(2, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of disjoint components among the nodes touched so
+    /// far, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// graph.connect(1, 2);
+    /// graph.add_node(3);
+    /// assert_eq!(2, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Adds the given node as a singleton, if it was never touched before,
+    /// and returns true only if the node was added.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// assert!(graph.add_node(3));
+    /// assert!(!graph.add_node(3));
+    /// ```
+    pub fn add_node(&mut self, id: u64) -> bool {
+        if self.parents.contains_key(&id) {
+            return false;
+        }
+        self.parents.insert(id, id);
+        self.sizes.insert(id, 1);
+        self.components += 1;
+        true
+    }
+
+    /// Returns true only if the given node was touched so far.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn contains(&self, id: u64) -> bool {
+        self.parents.contains_key(&id)
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// graph.connect(10, 20);
+    /// assert_eq!(2, graph.component_size(20));
+    /// assert_eq!(1, graph.component_size(30));
+    /// ```
+    pub fn component_size(&mut self, id: u64) -> u64 {
+        let root = self.find(id);
+        self.sizes.get(&root).cloned().unwrap_or(1)
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// assert!(!graph.connected(0, u64::MAX));
+    /// ```
+    pub fn connected(&mut self, a: u64, b: u64) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes, adding them if they were never touched.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// assert!(graph.connect(0, u64::MAX).is_some());
+    /// assert!(graph.connect(u64::MAX, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: u64, b: u64) -> Option<u64> {
+        self.add_node(a);
+        self.add_node(b);
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        let a_size = self.sizes[&a_root];
+        let b_size = self.sizes[&b_root];
+        // balance by linking root of smaller tree to root of larger tree
+        let (root, child) = if a_size < b_size {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.parents.insert(child, root);
+        self.sizes.remove(&child);
+        self.sizes.insert(root, a_size + b_size);
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node, which is the node itself if it was
+    /// never touched.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::SparseGraph::new();
+    /// graph.connect(1, 2);
+    /// assert_eq!(graph.find(1), graph.find(2));
+    /// assert_eq!(3, graph.find(3));
+    /// ```
+    pub fn find(&mut self, id: u64) -> u64 {
+        let mut node = id;
+        loop {
+            let parent = match self.parents.get(&node) {
+                Some(&parent) if parent != node => parent,
+                _ => return node,
+            };
+            let grandparent = self.parents[&parent];
+            // make every other node in path point to its grandparent
+            self.parents.insert(node, grandparent);
+            node = grandparent;
+        }
+    }
+}
+
+impl UnionFind for SparseGraph {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        SparseGraph::find(self, id as u64) as usize
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a as u64, b as u64).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect_sparse_ids() {
+        let mut graph = SparseGraph::new();
+        let ids = [u64::MAX, 1 << 50, 3, 1 << 33];
+        assert!(graph.connect(ids[0], ids[1]).is_some());
+        assert!(graph.connect(ids[2], ids[3]).is_some());
+        assert_eq!(4, graph.count());
+        assert_eq!(2, graph.component_count());
+        assert!(!graph.connected(ids[0], ids[2]));
+        assert!(graph.connect(ids[3], ids[1]).is_some());
+        assert!(ids.iter().all(|&id| graph.component_size(id) == 4));
+        assert_eq!(1, graph.component_count());
+        // queries do not touch new nodes
+        assert!(!graph.connected(5, 6));
+        assert!(!graph.contains(5));
+        assert_eq!(4, graph.count());
+        assert_eq!(1, graph.sizes.len());
+    }
+}
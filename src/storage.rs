@@ -0,0 +1,572 @@
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+use std::boxed::Box;
+use std::vec::Vec;
+
+use error::WufError;
+use union_find::UnionFind;
+
+/// Backend storing the parent and the size of each node of a `StorageGraph`,
+/// so that the arrays can live anywhere (such as on the heap, packed in
+/// narrower integers, or in a memory-mapped file) without changing the
+/// algorithms.
+/// 
+/// Implemented by `Vec<usize>` and `Box<[u32]>`, storing the parent and the
+/// size of each node next to each other (hence holding `len() / 2` nodes),
+/// in any allocator with the `allocator_api` feature (on nightly), and by
+/// `MappedStorage` with the `mmap` feature.
+/// 
+/// # Example
+/// ```
+/// use wuf::{Storage, StorageGraph};
+/// 
+/// // stores the parents only, approximating every size with 1
+/// struct Parents(Vec<usize>);
+/// 
+/// impl Storage for Parents {
+///     fn len(&self) -> usize { self.0.len() }
+///     fn parent(&self, id: usize) -> usize { self.0[id] }
+///     fn set_parent(&mut self, id: usize, parent: usize) { self.0[id] = parent; }
+///     fn size(&self, _: usize) -> usize { 1 }
+///     fn set_size(&mut self, _: usize, _: usize) {}
+/// }
+/// 
+/// let mut graph = StorageGraph::new(Parents(vec![0; 10]));
+/// graph.connect(0, 1);
+/// assert!(graph.connected(1, 0));
+/// ```
+pub trait Storage {
+    /// Returns the number of nodes.
+    fn len(&self) -> usize;
+
+    /// Returns true only if there are no nodes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the parent of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    fn parent(&self, id: usize) -> usize;
+
+    /// Sets the parent of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// * `parent` ID of its new parent.
+    fn set_parent(&mut self, id: usize, parent: usize);
+
+    /// Returns the size of the tree rooted at the given node (only meaningful
+    /// for roots).
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    fn size(&self, id: usize) -> usize;
+
+    /// Sets the size of the tree rooted at the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// * `size` Number of nodes of its tree.
+    fn set_size(&mut self, id: usize, size: usize);
+}
+
+// implements Storage for the given container, generic over its allocator
+// with the `allocator_api` feature
+macro_rules! impl_storage {
+    ($(#[$attr:meta])* $container:ident<$word:ty> $body:tt) => {
+        $(#[$attr])*
+        #[cfg(not(feature = "allocator_api"))]
+        impl Storage for $container<$word> $body
+
+        $(#[$attr])*
+        #[cfg(feature = "allocator_api")]
+        impl<A: Allocator> Storage for $container<$word, A> $body
+    };
+}
+
+impl_storage!(Vec<usize> {
+    fn len(&self) -> usize {
+        self[..].len() / 2
+    }
+
+    fn parent(&self, id: usize) -> usize {
+        self[2 * id]
+    }
+
+    fn set_parent(&mut self, id: usize, parent: usize) {
+        self[2 * id] = parent;
+    }
+
+    fn size(&self, id: usize) -> usize {
+        self[2 * id + 1]
+    }
+
+    fn set_size(&mut self, id: usize, size: usize) {
+        self[2 * id + 1] = size;
+    }
+});
+
+impl_storage!(
+    /// Packs each parent and size in 4 bytes, hence holding fewer than 2^32
+    /// nodes.
+    Box<[u32]> {
+        fn len(&self) -> usize {
+            self[..].len() / 2
+        }
+
+        fn parent(&self, id: usize) -> usize {
+            self[2 * id] as usize
+        }
+
+        fn set_parent(&mut self, id: usize, parent: usize) {
+            debug_assert!(parent <= u32::MAX as usize);
+            self[2 * id] = parent as u32;
+        }
+
+        fn size(&self, id: usize) -> usize {
+            self[2 * id + 1] as usize
+        }
+
+        fn set_size(&mut self, id: usize, size: usize) {
+            debug_assert!(size <= u32::MAX as usize);
+            self[2 * id + 1] = size as u32;
+        }
+    }
+);
+
+/// Weighted union-find over the nodes of the given `Storage`, linking roots
+/// by size and compressing paths by halving.
+/// 
+/// This is a separate, smaller union-find rather than `Graph` itself over a
+/// storage: `Graph` keeps its arrays in `Vec`s, since its other features (such
+/// as ranks, member lists, and node retirement) depend on them, while a
+/// StorageGraph only offers the core operations.
+/// 
+/// # Example
+/// ```
+/// let storage: Box<[u32]> = vec![0; 20].into_boxed_slice();
+/// let mut graph = wuf::StorageGraph::new(storage);
+/// graph.connect(0, 1);
+/// assert_eq!(10, graph.count());
+/// assert_eq!(9, graph.component_count());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StorageGraph<S: Storage> {
+    storage: S,         // parent and size of each node
+    components: usize   // number of disjoint components
+}
+
+impl<S: Storage> StorageGraph<S> {
+
+    /// Returns a new StorageGraph over the nodes of the given storage, where
+    /// every node is initialized as its own component.
+    /// 
+    /// # Arguments
+    /// * `storage` The storage of the nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// assert_eq!(10, graph.component_count());
+    /// ```
+    pub fn new(mut storage: S) -> StorageGraph<S> {
+        let n = storage.len();
+        for id in 0..n {
+            storage.set_parent(id, id);
+            storage.set_size(id, 1);
+        }
+        StorageGraph { storage, components: n }
+    }
+
+    /// Returns a StorageGraph over the nodes of the given storage, resuming
+    /// from the parents and sizes it already holds.
+    /// 
+    /// Returns an error if a parent is out of bounds, if the parents form a
+    /// cycle, or if the size of a root does not match its tree, visiting each
+    /// node once.
+    /// 
+    /// # Arguments
+    /// * `storage` The storage of the nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::from_storage(vec![1, 1, 1, 2]).unwrap();
+    /// assert!(graph.connected(0, 1));
+    /// assert!(wuf::StorageGraph::from_storage(vec![2, 1]).is_err());
+    /// assert!(wuf::StorageGraph::from_storage(vec![1, 1, 0, 1]).is_err());
+    /// ```
+    pub fn from_storage(storage: S) -> Result<StorageGraph<S>, WufError> {
+        let n = storage.len();
+        // resolve the root of every node: roots are resolved first, then every
+        // path is walked until a resolved node
+        const UNRESOLVED: usize = usize::MAX;
+        const VISITED: usize = usize::MAX - 1;
+        let mut roots = vec![UNRESOLVED; n];
+        let mut path = Vec::new();
+        for id in 0..n {
+            let mut node = id;
+            while roots[node] == UNRESOLVED {
+                let parent = storage.parent(node);
+                if parent >= n {
+                    return Err(WufError::InvalidParent { id: node, parent });
+                }
+                if parent == node {
+                    roots[node] = node;
+                    break;
+                }
+                roots[node] = VISITED;
+                path.push(node);
+                node = parent;
+            }
+            if roots[node] == VISITED {
+                return Err(WufError::Cycle { id: node });
+            }
+            let root = roots[node];
+            for node in path.drain(..) {
+                roots[node] = root;
+            }
+        }
+        let mut sizes = vec![0; n];
+        for &root in &roots {
+            sizes[root] += 1;
+        }
+        let mut components = 0;
+        for id in (0..n).filter(|&id| roots[id] == id) {
+            if storage.size(id) != sizes[id] {
+                return Err(WufError::SizeMismatch {
+                    id,
+                    expected: sizes[id],
+                    found: storage.size(id)
+                });
+            }
+            components += 1;
+        }
+        Ok(StorageGraph { storage, components })
+    }
+
+    /// Returns a StorageGraph over the nodes of the given storage, resuming
+    /// from the parents it already holds and recomputing the size of every
+    /// root, such as after a job stopped between the two writes of `connect`.
+    /// 
+    /// Unlike `from_storage`, this takes no memory besides the storage, using
+    /// the sizes of the nodes as scratch space (hence the storage must keep
+    /// the sizes it is given), and points every node directly to its root.
+    /// Returns an error if a parent is out of bounds or if the parents form a
+    /// cycle, in which case the sizes stored are left unspecified.
+    /// 
+    /// # Arguments
+    /// * `storage` The storage of the nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// // the size of the root 1 was not updated before the job stopped
+    /// let mut graph = wuf::StorageGraph::recover(vec![1, 1, 1, 1, 1, 1]).unwrap();
+    /// assert_eq!(3, graph.component_size(0));
+    /// assert!(wuf::StorageGraph::recover(vec![1, 0, 0, 0]).is_err());
+    /// ```
+    pub fn recover(mut storage: S) -> Result<StorageGraph<S>, WufError> {
+        let n = storage.len();
+        for id in 0..n {
+            storage.set_size(id, 0);
+        }
+        // walk the path of every node until a root or a node already pointing
+        // to its root, marking the nodes walked with the ID of the walk (plus
+        // one) to detect cycles, then point them to the root
+        for id in 0..n {
+            let mark = id + 1;
+            let mut node = id;
+            let root = loop {
+                let parent = storage.parent(node);
+                if parent >= n {
+                    return Err(WufError::InvalidParent { id: node, parent });
+                }
+                match storage.size(node) {
+                    0 if parent == node => break node,
+                    0 => {
+                        storage.set_size(node, mark);
+                        node = parent;
+                    }
+                    walk if walk == mark => return Err(WufError::Cycle { id: node }),
+                    _ => break parent
+                }
+            };
+            let mut node = id;
+            while node != root && storage.size(node) == mark {
+                let parent = storage.parent(node);
+                storage.set_parent(node, root);
+                node = parent;
+            }
+        }
+        let mut components = 0;
+        for id in 0..n {
+            let root = storage.parent(id);
+            if root == id {
+                components += 1;
+            } else {
+                storage.set_size(id, 0);
+            }
+            let size = storage.size(root);
+            storage.set_size(root, size + 1);
+        }
+        Ok(StorageGraph { storage, components })
+    }
+
+    /// Returns the storage of the nodes.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the storage of the nodes, consuming the graph.
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    /// Returns the number of nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the number of nodes in the component the given node belongs to.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// graph.connect(0, 1);
+    /// assert_eq!(2, graph.component_size(1));
+    /// ```
+    pub fn component_size(&mut self, id: usize) -> usize {
+        let root = self.find(id);
+        self.storage.size(root)
+    }
+
+    /// Returns true only if the two given nodes are connected,
+    /// otherwise returns false.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// assert!(!graph.connected(0, 1));
+    /// ```
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Connects the two given nodes.
+    /// 
+    /// Returns the root of the merged component if the two nodes belonged to
+    /// different components, otherwise returns `None`.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// assert!(graph.connect(0, 1).is_some());
+    /// assert!(graph.connect(1, 0).is_none());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize) -> Option<usize> {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        if a_root == b_root {
+            return None;
+        }
+        let a_size = self.storage.size(a_root);
+        let b_size = self.storage.size(b_root);
+        // balance by linking root of smaller tree to root of larger tree
+        let (root, child) = if a_size < b_size {
+            (b_root, a_root)
+        } else {
+            (a_root, b_root)
+        };
+        self.storage.set_size(root, a_size + b_size);
+        self.storage.set_parent(child, root);
+        self.components -= 1;
+        Some(root)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::StorageGraph::new(vec![0; 20]);
+    /// graph.connect(0, 1);
+    /// assert_eq!(graph.find(0), graph.find(1));
+    /// ```
+    pub fn find(&mut self, id: usize) -> usize {
+        assert!(id < self.count(), "node {} out of bounds", id);
+        let mut node = id;
+        let mut parent = self.storage.parent(node);
+        while node != parent {
+            // make every other node in path point to its grandparent
+            let grandparent = self.storage.parent(parent);
+            self.storage.set_parent(node, grandparent);
+            node = grandparent;
+            parent = self.storage.parent(node);
+        }
+        node
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<A: Allocator> StorageGraph<Vec<usize, A>> {
+
+    /// Returns a new StorageGraph with the given number of nodes, whose
+    /// storage lives in the given allocator (such as an arena, or an
+    /// allocator backed by huge pages).
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// * `alloc` The allocator of the storage.
+    /// 
+    /// # Example
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// 
+    /// let mut graph = wuf::StorageGraph::with_nodes_in(10, Global);
+    /// graph.connect(0, 1);
+    /// assert_eq!(9, graph.component_count());
+    /// ```
+    pub fn with_nodes_in(n: usize, alloc: A) -> StorageGraph<Vec<usize, A>> {
+        let mut words = Vec::with_capacity_in(2 * n, alloc);
+        words.resize(2 * n, 0);
+        StorageGraph::new(words)
+    }
+}
+
+impl<S: Storage> UnionFind for StorageGraph<S> {
+    fn len(&self) -> usize {
+        self.count()
+    }
+
+    fn find(&mut self, id: usize) -> usize {
+        StorageGraph::find(self, id)
+    }
+
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        self.connect(a, b).is_some()
+    }
+
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        StorageGraph::connected(self, a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_connect_any_storage() {
+        let edges = [(0, 1), (2, 3), (1, 3), (4, 5)];
+        let mut wide = StorageGraph::new(vec![0; 12]);
+        let mut packed = StorageGraph::new(vec![0u32; 12].into_boxed_slice());
+        for &(a, b) in &edges {
+            assert_eq!(wide.connect(a, b), packed.connect(a, b));
+        }
+        assert_eq!(2, packed.component_count());
+        assert_eq!(4, packed.component_size(2));
+        assert!(!packed.connected(0, 5));
+        let mut resumed = StorageGraph::from_storage(wide.into_storage()).unwrap();
+        assert_eq!(2, resumed.component_count());
+        assert!(resumed.connected(0, 2));
+        assert_eq!(
+            Err(WufError::InvalidParent { id: 1, parent: 3 }),
+            StorageGraph::from_storage(vec![0, 1, 3, 1]).map(drop)
+        );
+        assert_eq!(
+            Err(WufError::Cycle { id: 0 }),
+            StorageGraph::from_storage(vec![1, 1, 0, 1, 2, 1]).map(drop)
+        );
+        assert_eq!(
+            Err(WufError::SizeMismatch { id: 1, expected: 2, found: 1 }),
+            StorageGraph::from_storage(vec![1, 0, 1, 1]).map(drop)
+        );
+    }
+
+    #[test]
+    fn should_recover_sizes() {
+        // a chain whose sizes are all stale
+        let n = 1000;
+        let mut words = vec![7; 2 * n];
+        for id in 0..n {
+            words[2 * id] = id.saturating_sub(1);
+        }
+        let mut graph = StorageGraph::recover(words).unwrap();
+        assert_eq!(1, graph.component_count());
+        assert_eq!(n, graph.component_size(n - 1));
+        assert!((0..n).all(|id| graph.storage().parent(id) == 0));
+        let mut graph = StorageGraph::recover(vec![2, 0, 2, 0, 2, 0, 3, 0]).unwrap();
+        assert_eq!(2, graph.component_count());
+        assert_eq!(3, graph.component_size(1));
+        assert_eq!(1, graph.component_size(3));
+        assert_eq!(
+            Err(WufError::InvalidParent { id: 1, parent: 3 }),
+            StorageGraph::recover(vec![0, 1, 3, 1]).map(drop)
+        );
+        assert_eq!(
+            Err(WufError::Cycle { id: 0 }),
+            StorageGraph::recover(vec![1, 1, 0, 1, 2, 1]).map(drop)
+        );
+    }
+
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn should_allocate_in_allocator() {
+        use std::alloc::{AllocError, Global, Layout};
+        use std::cell::Cell;
+        use std::ptr::NonNull;
+
+        /// Allocator counting the bytes it allocates.
+        struct Counting<'a>(&'a Cell<usize>);
+
+        unsafe impl<'a> Allocator for Counting<'a> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + layout.size());
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let allocated = Cell::new(0);
+        let mut graph = StorageGraph::with_nodes_in(8, Counting(&allocated));
+        graph.connect(0, 7);
+        assert!(graph.connected(7, 0));
+        assert_eq!(16 * ::std::mem::size_of::<usize>(), allocated.get());
+    }
+}
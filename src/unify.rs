@@ -0,0 +1,238 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use graph::Graph;
+
+/// Key of a `UnificationTable`, usually a newtype around the index of the
+/// key in the table (such as the ID of a type variable).
+pub trait UnifyKey: Copy + Eq + Debug {
+    /// Returns the index of the key in the table.
+    fn index(self) -> usize;
+
+    /// Returns the key with the given index in the table.
+    /// 
+    /// # Arguments
+    /// * `index` Index of the key in the table.
+    fn from_index(index: usize) -> Self;
+}
+
+impl UnifyKey for usize {
+    #[inline]
+    fn index(self) -> usize {
+        self
+    }
+
+    #[inline]
+    fn from_index(index: usize) -> Self {
+        index
+    }
+}
+
+impl UnifyKey for u32 {
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_index(index: usize) -> Self {
+        debug_assert!(index <= u32::MAX as usize);
+        index as u32
+    }
+}
+
+/// Value carried by the keys of a `UnificationTable`, such as the type bound
+/// to a type variable.
+pub trait UnifyValue: Clone {
+    /// Error returned when two values cannot be unified.
+    type Error;
+
+    /// Returns the unification of the two given values, or an error if they
+    /// are incompatible.
+    /// 
+    /// # Arguments
+    /// * `a` The value of the first key.
+    /// * `b` The value of the second key.
+    fn unify_values(a: &Self, b: &Self) -> Result<Self, Self::Error>;
+}
+
+/// Union-find over keys that carry optional values, in the style of the
+/// unification tables of type-inference engines: unifying two keys unifies
+/// their values (see `UnifyValue`), and fails if the values are incompatible.
+/// 
+/// # Example
+/// ```
+/// use wuf::{UnificationTable, UnifyValue};
+/// 
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Type { Int, Bool }
+/// 
+/// impl UnifyValue for Type {
+///     type Error = (Type, Type);
+/// 
+///     fn unify_values(a: &Type, b: &Type) -> Result<Type, (Type, Type)> {
+///         if a == b { Ok(a.clone()) } else { Err((a.clone(), b.clone())) }
+///     }
+/// }
+/// 
+/// let mut table: UnificationTable<usize, Type> = UnificationTable::new();
+/// let (x, y, z) = (table.new_key(None), table.new_key(None), table.new_key(Some(Type::Int)));
+/// table.unify(x, y).unwrap();
+/// table.unify(y, z).unwrap();
+/// assert_eq!(Some(&Type::Int), table.probe(x));
+/// let b = table.new_key(Some(Type::Bool));
+/// assert_eq!(Err((Type::Int, Type::Bool)), table.unify(x, b));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnificationTable<K, V> {
+    graph: Graph,               // components of the keys
+    values: Vec<Option<V>>,     // value of the component of each root
+    keys: PhantomData<K>        // type of the keys
+}
+
+impl<K: UnifyKey, V: UnifyValue> UnificationTable<K, V> {
+
+    /// Returns a new empty UnificationTable.
+    pub fn new() -> UnificationTable<K, V> {
+        UnificationTable { graph: Graph::new(0), values: Vec::new(), keys: PhantomData }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true only if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a new key, not unified with any other key, with the given
+    /// value.
+    /// 
+    /// # Arguments
+    /// * `value` The value of the key, if any.
+    pub fn new_key(&mut self, value: Option<V>) -> K {
+        let index = self.graph.add_nodes(1).start;
+        self.values.push(value);
+        K::from_index(index)
+    }
+
+    /// Returns the representative key of the given key, shared by every key
+    /// unified with it.
+    /// 
+    /// # Arguments
+    /// * `key` The key.
+    pub fn find(&mut self, key: K) -> K {
+        K::from_index(self.graph.find(key.index()))
+    }
+
+    /// Returns true only if the two given keys are unified.
+    /// 
+    /// # Arguments
+    /// * `a` The first key.
+    /// * `b` The second key.
+    pub fn unioned(&mut self, a: K, b: K) -> bool {
+        self.graph.connected(a.index(), b.index())
+    }
+
+    /// Returns the value of the given key (shared by every key unified with
+    /// it), if any.
+    /// 
+    /// # Arguments
+    /// * `key` The key.
+    pub fn probe(&mut self, key: K) -> Option<&V> {
+        let root = self.graph.find(key.index());
+        self.values[root].as_ref()
+    }
+
+    /// Unifies the two given keys, and their values.
+    /// 
+    /// Returns an error if both keys have a value and the values cannot be
+    /// unified, in which case the keys are not unified.
+    /// 
+    /// # Arguments
+    /// * `a` The first key.
+    /// * `b` The second key.
+    pub fn unify(&mut self, a: K, b: K) -> Result<(), V::Error> {
+        let a_root = self.graph.find(a.index());
+        let b_root = self.graph.find(b.index());
+        if a_root == b_root {
+            return Ok(());
+        }
+        let value = self.unified_value(a_root, self.values[b_root].as_ref())?;
+        // the roots are different, hence the union succeeds
+        let root = self.graph.connect(a_root, b_root).unwrap();
+        let child = if root == a_root { b_root } else { a_root };
+        self.values[child] = None;
+        self.values[root] = value;
+        Ok(())
+    }
+
+    /// Unifies the value of the given key with the given value.
+    /// 
+    /// Returns an error if the key has a value that cannot be unified with
+    /// the given value, in which case the value of the key is unchanged.
+    /// 
+    /// # Arguments
+    /// * `key` The key.
+    /// * `value` The value to unify with the value of the key.
+    pub fn unify_value(&mut self, key: K, value: V) -> Result<(), V::Error> {
+        let root = self.graph.find(key.index());
+        self.values[root] = self.unified_value(root, Some(&value))?;
+        Ok(())
+    }
+
+    /// Returns the unification of the value of the given root with the given
+    /// value.
+    fn unified_value(&self, root: usize, value: Option<&V>) -> Result<Option<V>, V::Error> {
+        match (self.values[root].as_ref(), value) {
+            (Some(a), Some(b)) => V::unify_values(a, b).map(Some),
+            (Some(a), None) => Ok(Some(a.clone())),
+            (None, b) => Ok(b.cloned())
+        }
+    }
+}
+
+impl<K: UnifyKey, V: UnifyValue> Default for UnificationTable<K, V> {
+    fn default() -> Self {
+        UnificationTable::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Closed interval of integers, unified by intersection.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Interval(i32, i32);
+
+    impl UnifyValue for Interval {
+        type Error = ();
+
+        fn unify_values(a: &Interval, b: &Interval) -> Result<Interval, ()> {
+            let interval = Interval(a.0.max(b.0), a.1.min(b.1));
+            if interval.0 <= interval.1 { Ok(interval) } else { Err(()) }
+        }
+    }
+
+    #[test]
+    fn should_unify_values() {
+        let mut table: UnificationTable<u32, Interval> = UnificationTable::default();
+        let keys: Vec<u32> = (0..5).map(|_| table.new_key(None)).collect();
+        table.unify_value(keys[0], Interval(0, 10)).unwrap();
+        table.unify_value(keys[1], Interval(5, 20)).unwrap();
+        table.unify_value(keys[2], Interval(15, 30)).unwrap();
+        table.unify(keys[3], keys[0]).unwrap();
+        table.unify(keys[1], keys[3]).unwrap();
+        assert_eq!(Some(&Interval(5, 10)), table.probe(keys[0]));
+        assert_eq!(Err(()), table.unify(keys[2], keys[0]));
+        assert!(!table.unioned(keys[2], keys[0]));
+        assert_eq!(Err(()), table.unify_value(keys[1], Interval(11, 12)));
+        assert_eq!(Some(&Interval(5, 10)), table.probe(keys[3]));
+        assert_eq!(None, table.probe(keys[4]));
+        assert_eq!(table.find(keys[0]), table.find(keys[1]));
+        assert_eq!(5, table.len());
+    }
+}
@@ -0,0 +1,90 @@
+/// Common interface of the union-find structures of this crate, so that
+/// implementations can be swapped without rewriting call sites.
+/// 
+/// Implemented by `Graph` (weighted linking with path compression), as well
+/// as by `ArrayGraph`, `PackedGraph`, `RemGraph`, `RandomizedGraph`,
+/// `RollbackGraph`, `PersistentGraph`, `SharedGraph`, `SmallGraph`,
+/// `StorageGraph` (hence `MappedGraph`), `concurrent::AtomicGraph`,
+/// `concurrent::StripedGraph`, and the baselines `QuickFind` and `QuickUnion`.
+/// 
+/// # Example
+/// ```
+/// use wuf::UnionFind;
+/// 
+/// fn count_merges<U: UnionFind>(uf: &mut U, edges: &[(usize, usize)]) -> usize {
+///     edges.iter().filter(|&&(a, b)| uf.union(a, b)).count()
+/// }
+/// 
+/// assert_eq!(2, count_merges(&mut wuf::Graph::new(4), &[(0, 1), (1, 2), (2, 0)]));
+/// assert_eq!(2, count_merges(&mut wuf::Graph::<u8>::with_nodes(4), &[(0, 1), (1, 2), (2, 0)]));
+/// ```
+pub trait UnionFind {
+    /// Returns the number of nodes.
+    fn len(&self) -> usize;
+
+    /// Returns true only if there are no nodes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    fn find(&mut self, id: usize) -> usize;
+
+    /// Connects the two given nodes, returning true only if they belonged to
+    /// different components.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    fn union(&mut self, a: usize, b: usize) -> bool;
+
+    /// Returns true only if the two given nodes are connected.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use concurrent::{AtomicGraph, StripedGraph};
+    use {
+        ArrayGraph, Graph, PackedGraph, PersistentGraph, QuickFind, QuickUnion, RandomizedGraph,
+        RemGraph, RollbackGraph, SharedGraph, SmallGraph, StorageGraph
+    };
+
+    /// Connects the given edges and returns the resulting connectivity matrix.
+    fn connectivity<U: UnionFind>(mut uf: U, edges: &[(usize, usize)]) -> Vec<bool> {
+        let merges: Vec<bool> = edges.iter().map(|&(a, b)| uf.union(a, b)).collect();
+        assert_eq!(vec![true, true, true, false, true, false], merges);
+        let n = uf.len();
+        (0..n * n).map(|i| uf.connected(i / n, i % n)).collect()
+    }
+
+    #[test]
+    fn should_agree_across_implementations() {
+        let edges = [(0, 5), (3, 1), (5, 3), (1, 0), (6, 7), (7, 6)];
+        let expected = connectivity(QuickFind::new(8), &edges);
+        assert_eq!(expected, connectivity(QuickUnion::new(8), &edges));
+        assert_eq!(expected, connectivity(Graph::new(8), &edges));
+        assert_eq!(expected, connectivity(ArrayGraph::<8>::new(), &edges));
+        assert_eq!(expected, connectivity(PackedGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(RemGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(RandomizedGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(RollbackGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(PersistentGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(SharedGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(SmallGraph::<4>::new(8), &edges));
+        assert_eq!(expected, connectivity(StorageGraph::new(vec![0; 16]), &edges));
+        assert_eq!(expected, connectivity(AtomicGraph::new(8), &edges));
+        assert_eq!(expected, connectivity(StripedGraph::with_stripes(8, 3), &edges));
+        assert!(!Graph::new(1).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! JavaScript bindings (with the `wasm` feature), exposing a `Graph` class
+//! to JavaScript through `wasm-bindgen`.
+//! 
+//! Node IDs are JavaScript numbers, and operations on nodes out of bounds
+//! throw an `Error` rather than aborting the module.
+//! 
+//! ```js
+//! import { Graph } from "wuf";
+//! 
+//! const graph = new Graph(10);
+//! graph.union(0, 1);
+//! graph.unionAll(new Uint32Array([2, 3, 3, 4]));
+//! console.log(graph.connected(2, 4), graph.componentCount());
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use graph::Graph;
+
+/// Union-find over a fixed range of nodes (see `Graph`), as a JavaScript
+/// class.
+#[wasm_bindgen(js_name = Graph)]
+#[derive(Debug, Clone)]
+pub struct WasmGraph {
+    graph: Graph    // graph the calls are forwarded to
+}
+
+#[wasm_bindgen(js_class = Graph)]
+impl WasmGraph {
+
+    /// Returns a new graph with the given number of nodes.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    #[wasm_bindgen(constructor)]
+    pub fn new(n: usize) -> WasmGraph {
+        WasmGraph { graph: Graph::new(n) }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.graph.count()
+    }
+
+    /// Returns the number of disjoint components.
+    #[wasm_bindgen(js_name = componentCount)]
+    pub fn component_count(&self) -> usize {
+        self.graph.component_count()
+    }
+
+    /// Connects the two given nodes, returning true only if they belonged to
+    /// different components.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn union(&mut self, a: usize, b: usize) -> Result<bool, JsError> {
+        self.graph.try_connect(a, b).map(|root| root.is_some()).map_err(to_js)
+    }
+
+    /// Connects the edges given as a flat array of pairs of node IDs (such as
+    /// a `Uint32Array`), and returns the number of merges. Throws on the
+    /// first edge out of bounds, after connecting the edges before it.
+    /// 
+    /// # Arguments
+    /// * `edges` The node IDs of the edges, two for every edge.
+    #[wasm_bindgen(js_name = unionAll)]
+    pub fn union_all(&mut self, edges: &[u32]) -> Result<usize, JsError> {
+        if !edges.len().is_multiple_of(2) {
+            return Err(JsError::new("the number of node IDs must be even"));
+        }
+        let mut merges = 0;
+        for pair in edges.chunks(2) {
+            if self.union(pair[0] as usize, pair[1] as usize)? {
+                merges += 1;
+            }
+        }
+        Ok(merges)
+    }
+
+    /// Returns the root of the given node.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> Result<usize, JsError> {
+        self.graph.try_find(id).map_err(to_js)
+    }
+
+    /// Returns true only if the two given nodes are connected.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn connected(&mut self, a: usize, b: usize) -> Result<bool, JsError> {
+        self.graph.try_connected(a, b).map_err(to_js)
+    }
+
+    /// Returns the label of every node (see `Graph::labels`), as a
+    /// `Uint32Array`.
+    pub fn labels(&self) -> Vec<u32> {
+        self.graph.labels().into_iter().map(|label| label as u32).collect()
+    }
+}
+
+/// Converts the given error into a JavaScript error.
+fn to_js<E: ::std::error::Error>(err: E) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_forward_to_graph() {
+        let mut graph = WasmGraph::new(6);
+        assert_eq!(Ok(true), graph.union(0, 1).map_err(drop));
+        assert_eq!(Ok(false), graph.union(1, 0).map_err(drop));
+        assert_eq!(Ok(2), graph.union_all(&[2, 3, 3, 1, 0, 2]).map_err(drop));
+        assert_eq!(Ok(true), graph.connected(0, 3).map_err(drop));
+        assert_eq!(Ok(graph.graph.find(3)), graph.find(2).map_err(drop));
+        assert_eq!(3, graph.component_count());
+        assert_eq!(vec![0, 0, 0, 0, 1, 2], graph.labels());
+    }
+}
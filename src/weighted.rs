@@ -0,0 +1,187 @@
+use error::WufError;
+
+/// Union-find where every node has a potential, known relative to the other
+/// nodes of its component, so that differences of potentials can be asserted
+/// and inferred, and inconsistent assertions are reported as contradictions.
+/// 
+/// This solves systems of difference constraints, such as "A is 3 heavier
+/// than B", where every component is determined up to a constant.
+/// 
+/// Potentials are added and subtracted with wrapping arithmetic (as `Group`
+/// does for integers), hence they are known modulo 2^64: a difference that
+/// does not fit in an `i64` wraps around rather than overflowing, and
+/// constraints only contradict each other if they differ modulo 2^64.
+/// 
+/// # Example
+/// ```
+/// use wuf::{WeightedGraph, WufError};
+/// 
+/// let mut graph = WeightedGraph::new(3);
+/// graph.connect(0, 1, 3).unwrap();
+/// graph.connect(1, 2, -5).unwrap();
+/// assert_eq!(Some(-2), graph.diff(0, 2));
+/// let err = graph.connect(2, 0, 1).unwrap_err();
+/// assert_eq!(WufError::Contradiction { a: 2, b: 0 }, err);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedGraph {
+    parents: Vec<usize>,    // parent of each node, roots are their own parent
+    offsets: Vec<i64>,      // potential of each node minus the potential of its parent
+    sizes: Vec<usize>,      // size of the tree of each root
+    components: usize       // number of disjoint components
+}
+
+impl WeightedGraph {
+
+    /// Returns a new WeightedGraph with the given number of nodes, without
+    /// constraints.
+    /// 
+    /// # Arguments
+    /// * `n` Number of nodes belonging to the graph.
+    /// 
+    /// # Example
+    /// ```
+    /// let graph = wuf::WeightedGraph::new(10);
+    /// assert_eq!(10, graph.count());
+    /// ```
+    pub fn new(n: usize) -> WeightedGraph {
+        WeightedGraph {
+            parents: (0..n).collect(),
+            offsets: vec![0; n],
+            sizes: vec![1; n],
+            components: n
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn count(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns the number of disjoint components, in constant time.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the root of the given node, and the potential of the node
+    /// minus the potential of its root.
+    /// 
+    /// # Arguments
+    /// * `id` ID of the node.
+    pub fn find(&mut self, id: usize) -> (usize, i64) {
+        let mut root = id;
+        let mut offset = 0i64;
+        while self.parents[root] != root {
+            offset = offset.wrapping_add(self.offsets[root]);
+            root = self.parents[root];
+        }
+        // make every node in path point to the root, with its own offset
+        let mut node = id;
+        let mut node_offset = offset;
+        while self.parents[node] != root && node != root {
+            let parent = self.parents[node];
+            let parent_offset = node_offset.wrapping_sub(self.offsets[node]);
+            self.parents[node] = root;
+            self.offsets[node] = node_offset;
+            node = parent;
+            node_offset = parent_offset;
+        }
+        (root, offset)
+    }
+
+    /// Returns the potential of `b` minus the potential of `a` implied by the
+    /// constraints asserted so far, or `None` if the nodes are not related.
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    pub fn diff(&mut self, a: usize, b: usize) -> Option<i64> {
+        let (a_root, a_offset) = self.find(a);
+        let (b_root, b_offset) = self.find(b);
+        if a_root == b_root {
+            Some(b_offset.wrapping_sub(a_offset))
+        } else {
+            None
+        }
+    }
+
+    /// Asserts that the potential of `b` minus the potential of `a` is `d`.
+    /// 
+    /// Returns true only if the two nodes belonged to different components,
+    /// or an error if the constraint contradicts the constraints asserted so
+    /// far (in which case the graph is not modified).
+    /// 
+    /// # Arguments
+    /// * `a` ID of the first node.
+    /// * `b` ID of the second node.
+    /// * `d` Difference between the potentials of the two nodes.
+    /// 
+    /// # Example
+    /// ```
+    /// let mut graph = wuf::WeightedGraph::new(10);
+    /// assert_eq!(Ok(true), graph.connect(0, 1, 4));
+    /// assert_eq!(Ok(false), graph.connect(1, 0, -4));
+    /// assert!(graph.connect(0, 1, 5).is_err());
+    /// ```
+    pub fn connect(&mut self, a: usize, b: usize, d: i64) -> Result<bool, WufError> {
+        let (a_root, a_offset) = self.find(a);
+        let (b_root, b_offset) = self.find(b);
+        if a_root == b_root {
+            return if b_offset.wrapping_sub(a_offset) == d {
+                Ok(false)
+            } else {
+                Err(WufError::Contradiction { a, b })
+            };
+        }
+        // potential of the root of b minus the potential of the root of a
+        let roots_diff = a_offset.wrapping_add(d).wrapping_sub(b_offset);
+        // link the smaller tree below the larger one
+        if self.sizes[a_root] < self.sizes[b_root] {
+            self.parents[a_root] = b_root;
+            self.offsets[a_root] = roots_diff.wrapping_neg();
+            self.sizes[b_root] += self.sizes[a_root];
+        } else {
+            self.parents[b_root] = a_root;
+            self.offsets[b_root] = roots_diff;
+            self.sizes[a_root] += self.sizes[b_root];
+        }
+        self.components -= 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_infer_differences() {
+        // potentials to recover, asserted through a chain of constraints
+        let potentials = [7i64, -3, 12, 0, 5, 5, -8];
+        let mut graph = WeightedGraph::new(potentials.len());
+        for &(a, b) in &[(0, 1), (2, 3), (1, 3), (6, 5), (4, 6), (3, 5)] {
+            assert_eq!(Ok(true), graph.connect(a, b, potentials[b] - potentials[a]));
+        }
+        for a in 0..potentials.len() {
+            for b in 0..potentials.len() {
+                assert_eq!(Some(potentials[b] - potentials[a]), graph.diff(a, b));
+            }
+        }
+        assert!(graph.connect(4, 5, 1).is_err());
+        assert_eq!(Ok(false), graph.connect(4, 5, 0));
+        assert_eq!(None, WeightedGraph::new(2).diff(0, 1));
+    }
+
+    #[test]
+    fn should_wrap_around() {
+        let mut graph = WeightedGraph::new(4);
+        assert_eq!(Ok(true), graph.connect(0, 1, i64::MAX));
+        assert_eq!(Ok(true), graph.connect(1, 2, i64::MAX));
+        assert_eq!(Ok(true), graph.connect(3, 2, i64::MIN));
+        assert_eq!(Some(-2), graph.diff(0, 2));
+        assert_eq!(Some(2), graph.diff(2, 0));
+        assert_eq!(Some(i64::MIN), graph.diff(2, 3));
+        assert_eq!(Ok(false), graph.connect(0, 2, -2));
+        assert!(graph.connect(0, 2, i64::MAX).is_err());
+    }
+}